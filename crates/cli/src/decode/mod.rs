@@ -1,8 +1,11 @@
 mod parser;
 
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
 
 use anyhow::{bail, Error};
+use nom::Offset;
 use walrus::{CustomSection, Module, TypedCustomSectionId, ValType};
 
 use crate::util::*;
@@ -16,36 +19,289 @@ pub struct GodotWasmBindgenData {
 
 #[derive(Debug, Clone)]
 pub struct Symbol {
-    pub version: [u8; 4],
+    pub version: Version,
     pub inner: SymbolType,
 }
 
-#[derive(Debug, Clone)]
+/// Version of the metadata format, encoded as little-endian major and minor `u16`.
+///
+/// Minor version may only add symbol kinds, which older versions skip.
+/// Major version is incompatible, except that every symbol stays length-prefixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl Version {
+    /// Version supported by this crate.
+    pub const CURRENT: Self = Self { major: 2, minor: 0 };
+
+    pub fn is_compatible(self) -> bool {
+        self.major == Self::CURRENT.major
+    }
+
+    fn write(self, bytes: &mut Vec<u8>) {
+        bytes.extend(self.major.to_le_bytes());
+        bytes.extend(self.minor.to_le_bytes());
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SymbolType {
     ExportFunction(ExportFunction),
     ImportFunction(ImportFunction),
+    /// Host-provided static, imported as getter with a single result.
+    ImportStatic(ImportFunction),
+    /// Exported constant, exported as getter with a single result.
+    ExportConst(ExportFunction),
+    Class(Class),
+    Start(StartFunction),
+    /// Symbol of newer minor version, kept as is.
+    Unknown {
+        kind: u64,
+        data: Vec<u8>,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExportFunction {
+    pub class: Option<String>,
+    /// Public name, which is also the name of the wasm export.
     pub name: String,
     pub args: FunctionArgs,
+    pub doc: String,
+    /// Async function, which returns a task handle to be polled by host.
+    pub is_async: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Class {
+    pub name: String,
+    pub constructor: Option<String>,
+    pub destructor: String,
+    pub doc: String,
+}
+
+/// Function run once after instantiation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartFunction {
+    /// Name of the wasm export.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ImportFunction {
     pub module: String,
+    /// Public name (the `link_name` if set), which is also the name of the wasm import.
     pub name: String,
     pub args: FunctionArgs,
+    pub doc: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionArgs {
     pub params: Vec<ArgType>,
     pub results: Vec<ArgType>,
+    /// Names of parameters, same length as `params`.
+    pub param_names: Vec<String>,
+    /// Names of results, same length as `results`.
+    pub result_names: Vec<String>,
+    /// Number of trailing parameters with default value, which the host may pass as null.
+    pub optional: usize,
+    /// Value types of `GodotValue` parameters, `None` for other parameters.
+    pub param_types: Vec<Option<TypeInfo>>,
+    /// Value types of `GodotValue` results, `None` for other results.
+    pub result_types: Vec<Option<TypeInfo>>,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl FunctionArgs {
+    pub fn named_params(&self) -> impl Iterator<Item = (&str, ArgType)> {
+        self.param_names
+            .iter()
+            .map(|v| v as &str)
+            .zip(self.params.iter().copied())
+    }
+
+    /// Parameters which may be omitted by the caller.
+    pub fn optional_params(&self) -> impl Iterator<Item = (&str, ArgType)> {
+        self.named_params().skip(self.params.len() - self.optional)
+    }
+
+    pub fn named_results(&self) -> impl Iterator<Item = (&str, ArgType)> {
+        self.result_names
+            .iter()
+            .map(|v| v as &str)
+            .zip(self.results.iter().copied())
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        for (args, types) in [
+            (&self.params, &self.param_types),
+            (&self.results, &self.result_types),
+        ] {
+            leb128::write::unsigned(bytes, args.len() as _).unwrap();
+            for (&v, t) in args.iter().zip(types) {
+                write_arg(bytes, v, t.as_ref());
+            }
+        }
+
+        for names in [&self.param_names, &self.result_names] {
+            leb128::write::unsigned(bytes, names.len() as _).unwrap();
+            for n in names {
+                write_str(bytes, n);
+            }
+        }
+        leb128::write::unsigned(bytes, self.optional as _).unwrap();
+    }
+}
+
+fn write_str(bytes: &mut Vec<u8>, s: &str) {
+    leb128::write::unsigned(bytes, s.len() as _).unwrap();
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+/// `GodotValue` argument is followed by it's value type.
+fn write_arg(bytes: &mut Vec<u8>, ty: ArgType, info: Option<&TypeInfo>) {
+    bytes.push(ty as u8);
+    if let ArgType::GodotValue = ty {
+        info.unwrap_or(&TypeInfo::Any).write(bytes);
+    }
+}
+
+/// Type of Godot value passed as `GodotValue` argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeInfo {
+    Any,
+    Value(ValueType),
+    /// Either null or value of the inner type.
+    Optional(Box<TypeInfo>),
+    /// Array with elements of the inner type.
+    Array(Box<TypeInfo>),
+    /// Array of fixed length, with elements typed by position.
+    Tuple(Vec<TypeInfo>),
+    /// Dictionary with the named fields.
+    Struct(Vec<(String, TypeInfo)>),
+    PoolArray(PoolElement),
+}
+
+impl TypeInfo {
+    fn write(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Self::Any => bytes.push(0),
+            Self::Value(v) => bytes.extend([1, *v as u8]),
+            Self::Optional(v) => {
+                bytes.push(2);
+                v.write(bytes);
+            }
+            Self::Array(v) => {
+                bytes.push(3);
+                v.write(bytes);
+            }
+            Self::Tuple(v) => {
+                bytes.push(4);
+                leb128::write::unsigned(bytes, v.len() as _).unwrap();
+                for t in v {
+                    t.write(bytes);
+                }
+            }
+            Self::Struct(v) => {
+                bytes.push(5);
+                leb128::write::unsigned(bytes, v.len() as _).unwrap();
+                for (n, t) in v {
+                    write_str(bytes, n);
+                    t.write(bytes);
+                }
+            }
+            Self::PoolArray(v) => bytes.extend([6, *v as u8]),
+        }
+    }
+}
+
+/// Formatted similar to Rust types, with `Variant` being any value.
+impl fmt::Display for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => write!(f, "Variant"),
+            Self::Value(v) => write!(f, "{:?}", v),
+            Self::Optional(v) => write!(f, "Option<{}>", v),
+            Self::Array(v) => write!(f, "Array<{}>", v),
+            Self::Tuple(v) => {
+                write!(f, "(")?;
+                for (i, t) in v.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", t)?;
+                }
+                write!(f, ")")
+            }
+            Self::Struct(v) => {
+                write!(f, "{{")?;
+                for (i, (n, t)) in v.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {}: {}", n, t)?;
+                }
+                write!(f, " }}")
+            }
+            Self::PoolArray(v) => write!(f, "Pool{:?}Array", v),
+        }
+    }
+}
+
+/// Type of Godot value, same as `ValueType` of the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ValueType {
+    Bool = 1,
+    Int,
+    Float,
+    String,
+    Vector2,
+    Rect2,
+    Vector3,
+    Transform2D,
+    Plane,
+    Quat,
+    Aabb,
+    Basis,
+    Transform,
+    Color,
+    NodePath,
+    Rid,
+    Object,
+    Dictionary,
+    Array,
+    ByteArray,
+    IntArray,
+    FloatArray,
+    StringArray,
+    Vector2Array,
+    Vector3Array,
+    ColorArray,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PoolElement {
+    Byte = 1,
+    Int,
+    Float,
+    String,
+    Vector2,
+    Vector3,
+    Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum ArgType {
     U8 = 1,
@@ -59,6 +315,60 @@ pub enum ArgType {
     F32,
     F64,
     GodotValue,
+    Handle,
+    /// Borrowed value, only valid as import parameter.
+    GodotValueRef,
+    String,
+    ByteArray,
+    IntArray,
+    FloatArray,
+    Vector2Array,
+    Vector3Array,
+    ColorArray,
+}
+
+/// Array argument, passed as pointer and length pair in linear memory.
+#[derive(Debug, Clone, Copy)]
+pub struct ArrayInfo {
+    /// Prefix of the host functions (`len`, `read`, and `write`).
+    pub prefix: &'static str,
+    pub size: u32,
+    pub align: u32,
+}
+
+impl ArgType {
+    pub fn array_info(self) -> Option<ArrayInfo> {
+        let (prefix, size, align) = match self {
+            Self::String => ("string", 1, 1),
+            Self::ByteArray => ("byte_array", 1, 1),
+            Self::IntArray => ("int_array", 4, 4),
+            Self::FloatArray => ("float_array", 4, 4),
+            Self::Vector2Array => ("vector2_array", 8, 4),
+            Self::Vector3Array => ("vector3_array", 12, 4),
+            Self::ColorArray => ("color_array", 16, 4),
+            _ => return None,
+        };
+
+        Some(ArrayInfo {
+            prefix,
+            size,
+            align,
+        })
+    }
+
+    /// Types of the argument inside the module.
+    pub fn inner_types(self) -> &'static [ValType] {
+        if self.array_info().is_some() {
+            return &[ValType::I32, ValType::I32];
+        }
+
+        match ValType::from(self) {
+            ValType::I64 => &[ValType::I64],
+            ValType::F32 => &[ValType::F32],
+            ValType::F64 => &[ValType::F64],
+            _ => &[ValType::I32],
+        }
+    }
 }
 
 impl From<ArgType> for ValType {
@@ -69,23 +379,291 @@ impl From<ArgType> for ValType {
             | ArgType::U16
             | ArgType::I16
             | ArgType::U32
-            | ArgType::I32 => Self::I32,
+            | ArgType::I32
+            | ArgType::Handle => Self::I32,
             ArgType::U64 | ArgType::I64 => Self::I64,
             ArgType::F32 => Self::F32,
             ArgType::F64 => Self::F64,
-            ArgType::GodotValue => Self::Externref,
+            ArgType::GodotValue
+            | ArgType::GodotValueRef
+            | ArgType::String
+            | ArgType::ByteArray
+            | ArgType::IntArray
+            | ArgType::FloatArray
+            | ArgType::Vector2Array
+            | ArgType::Vector3Array
+            | ArgType::ColorArray => Self::Externref,
+        }
+    }
+}
+
+/// Identity of symbol, which must be unique.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum SymbolKey<'a> {
+    /// Name of wasm export, shared by functions, methods and constants.
+    Export(&'a str),
+    Import(&'a str, &'a str),
+    Class(&'a str),
+    Start(&'a str),
+}
+
+impl SymbolType {
+    fn key(&self) -> Option<SymbolKey<'_>> {
+        match self {
+            Self::ExportFunction(v) | Self::ExportConst(v) => Some(SymbolKey::Export(&v.name)),
+            Self::ImportFunction(v) | Self::ImportStatic(v) => {
+                Some(SymbolKey::Import(&v.module, &v.name))
+            }
+            Self::Class(v) => Some(SymbolKey::Class(&v.name)),
+            Self::Start(v) => Some(SymbolKey::Start(&v.name)),
+            Self::Unknown { .. } => None,
+        }
+    }
+
+    /// Checks if both symbols are the same, ignoring documentation.
+    fn same_signature(&self, other: &Self) -> bool {
+        fn strip_doc(v: &SymbolType) -> SymbolType {
+            let mut v = v.clone();
+            match &mut v {
+                SymbolType::ExportFunction(v) | SymbolType::ExportConst(v) => v.doc.clear(),
+                SymbolType::ImportFunction(v) | SymbolType::ImportStatic(v) => v.doc.clear(),
+                SymbolType::Class(v) => v.doc.clear(),
+                SymbolType::Start(_) | SymbolType::Unknown { .. } => (),
+            }
+            v
+        }
+
+        strip_doc(self) == strip_doc(other)
+    }
+}
+
+impl GodotWasmBindgenData {
+    /// Merges symbols duplicated by linking multiple crates.
+    ///
+    /// Symbols of the same identity but different signature are conflicting,
+    /// which is reported with both definitions.
+    pub fn merge_duplicates(&mut self) -> Result<(), Error> {
+        let conflicts = self.merge_identical();
+        if !conflicts.is_empty() {
+            bail!(
+                "Conflicting symbol definitions:\n{}",
+                conflicts.join("\n\n")
+            );
+        }
+        Ok(())
+    }
+
+    /// Merges identical symbols, keeping conflicting ones.
+    ///
+    /// Returns every conflict, formatted with both definitions.
+    pub fn merge_identical(&mut self) -> Vec<String> {
+        let mut keep = vec![true; self.symbols.len()];
+        let mut conflicts = Vec::new();
+        {
+            let mut first: HashMap<SymbolKey<'_>, &SymbolType> = HashMap::new();
+            for (s, keep) in self.symbols.iter().zip(keep.iter_mut()) {
+                let key = match s.inner.key() {
+                    Some(v) => v,
+                    None => continue,
+                };
+                match first.get(&key) {
+                    Some(v) if v.same_signature(&s.inner) => *keep = false,
+                    Some(v) => conflicts.push(format!("  {}\n  {}", v, s.inner)),
+                    None => {
+                        first.insert(key, &s.inner);
+                    }
+                }
+            }
+        }
+
+        let mut keep = keep.into_iter();
+        self.symbols.retain(|_| keep.next().unwrap());
+        conflicts
+    }
+}
+
+impl fmt::Display for SymbolType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExportFunction(v) => {
+                if v.is_async {
+                    write!(f, "async ")?;
+                }
+                match &v.class {
+                    Some(c) => write!(f, "method {} of class {}{}", v.name, c, v.args),
+                    None => write!(f, "function {}{}", v.name, v.args),
+                }
+            }
+            Self::ExportConst(v) => write!(f, "constant {}: {}", v.name, v.args.value_type()),
+            Self::ImportFunction(v) => write!(f, "import {}.{}{}", v.module, v.name, v.args),
+            Self::ImportStatic(v) => {
+                write!(f, "static {}.{}: {}", v.module, v.name, v.args.value_type())
+            }
+            Self::Class(v) => {
+                write!(f, "class {} (", v.name)?;
+                if let Some(c) = &v.constructor {
+                    write!(f, "constructor {}, ", c)?;
+                }
+                write!(f, "destructor {})", v.destructor)
+            }
+            Self::Start(v) => write!(f, "start function {}", v.name),
+            Self::Unknown { kind, .. } => write!(f, "unknown symbol of kind {}", kind),
+        }
+    }
+}
+
+impl FunctionArgs {
+    /// Type of constant or static, which is it's only result.
+    fn value_type(&self) -> String {
+        match (self.result_types.first(), self.results.first()) {
+            (Some(Some(t)), _) => t.to_string(),
+            (_, Some(a)) => format!("{:?}", a),
+            _ => "()".to_string(),
         }
     }
 }
 
+/// Formatted as `(name: type, ...) -> (name: type, ...)`.
+impl fmt::Display for FunctionArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_args(
+            f: &mut fmt::Formatter<'_>,
+            names: &[String],
+            args: &[ArgType],
+            types: &[Option<TypeInfo>],
+        ) -> fmt::Result {
+            write!(f, "(")?;
+            for (i, ((n, a), t)) in names.iter().zip(args).zip(types).enumerate() {
+                if i != 0 {
+                    write!(f, ", ")?;
+                }
+                match t {
+                    Some(t) => write!(f, "{}: {}", n, t)?,
+                    None => write!(f, "{}: {:?}", n, a)?,
+                }
+            }
+            write!(f, ")")
+        }
+
+        write_args(f, &self.param_names, &self.params, &self.param_types)?;
+        write!(f, " -> ")?;
+        write_args(f, &self.result_names, &self.results, &self.result_types)
+    }
+}
+
+/// Malformed custom section, located by byte offset from start of section.
+#[derive(Debug)]
+pub struct DecodeError {
+    pub section: &'static str,
+    pub offset: usize,
+    /// Index of symbol containing the error.
+    pub symbol: Option<usize>,
+    pub expected: Option<&'static str>,
+    pub cause: Option<String>,
+}
+
+impl DecodeError {
+    fn new(
+        section: &'static str,
+        data: &[u8],
+        symbol: Option<usize>,
+        e: nom::Err<parser::ParseError<'_>>,
+    ) -> Self {
+        match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => Self {
+                section,
+                offset: data.offset(e.input),
+                symbol,
+                expected: e.expected,
+                cause: e.cause.or_else(|| match e.kind {
+                    nom::error::ErrorKind::Eof | nom::error::ErrorKind::Complete => {
+                        Some("unexpected end of data".into())
+                    }
+                    _ => None,
+                }),
+            },
+            // Every parser is complete, so this should be unreachable.
+            nom::Err::Incomplete(_) => Self {
+                section,
+                offset: data.len(),
+                symbol,
+                expected: None,
+                cause: Some("unexpected end of data".into()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Malformed {} at offset {:#x}", self.section, self.offset)?;
+        if let Some(i) = self.symbol {
+            write!(f, " (symbol #{})", i)?;
+        }
+        match self.expected {
+            Some(v) => write!(f, ": expected {}", v)?,
+            None => write!(f, ": invalid data")?,
+        }
+        if let Some(v) = &self.cause {
+            write!(f, " ({})", v)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
 impl TryFrom<&[u8]> for GodotWasmBindgenData {
     type Error = Error;
 
     fn try_from(bytes: &[u8]) -> Result<Self, Error> {
-        match parser::parse_bindgen_data(bytes) {
-            Ok((_, ret)) => Ok(ret),
-            Err(e) => bail!("{}", e),
+        Self::decode(bytes, false)
+    }
+}
+
+impl GodotWasmBindgenData {
+    /// Decodes symbols, keeping symbols of incompatible version instead of failing.
+    ///
+    /// Such symbol may have different layout, so it's kept unparsed as unknown.
+    pub fn decode_lenient(bytes: &[u8]) -> Result<Self, Error> {
+        Self::decode(bytes, true)
+    }
+
+    fn decode(bytes: &[u8], lenient: bool) -> Result<Self, Error> {
+        let mut symbols = Vec::new();
+        let mut input = bytes;
+        while !input.is_empty() {
+            let index = symbols.len();
+            let start = input;
+            let (version, data);
+            (input, (version, data)) = parser::parse_symbol(input)
+                .map_err(|e| DecodeError::new(GODOT_WASM_BINDGEN_NAME, bytes, Some(index), e))?;
+
+            let parse = if version.is_compatible() {
+                parser::parse_symbol_type
+            } else if lenient {
+                parser::parse_unknown_symbol
+            } else {
+                return Err(DecodeError {
+                    section: GODOT_WASM_BINDGEN_NAME,
+                    offset: bytes.offset(start),
+                    symbol: Some(index),
+                    expected: Some("compatible symbol version"),
+                    cause: Some(format!(
+                        "version {} is incompatible with supported version {}.x, use matching releases of the library and CLI",
+                        version,
+                        Version::CURRENT.major,
+                    )),
+                }
+                .into());
+            };
+
+            let (_, inner) = parse(data)
+                .map_err(|e| DecodeError::new(GODOT_WASM_BINDGEN_NAME, bytes, Some(index), e))?;
+            symbols.push(Symbol { version, inner });
         }
+
+        Ok(Self { symbols })
     }
 }
 
@@ -94,7 +672,7 @@ impl CustomSection for GodotWasmBindgenData {
         GODOT_WASM_BINDGEN_NAME
     }
 
-    fn data(&self, _: &walrus::IdsToIndices) -> Cow<[u8]> {
+    fn data(&self, _: &walrus::IdsToIndices) -> Cow<'_, [u8]> {
         let mut ret = Vec::new();
 
         for s in &self.symbols {
@@ -104,37 +682,92 @@ impl CustomSection for GodotWasmBindgenData {
 
             match inner {
                 SymbolType::ExportFunction(ExportFunction {
+                    class,
                     name,
-                    args: FunctionArgs { params, results },
+                    args,
+                    doc,
+                    is_async,
                 }) => {
-                    leb128::write::unsigned(&mut temp, 64).unwrap();
-                    leb128::write::unsigned(&mut temp, name.len() as _).unwrap();
-                    temp.extend_from_slice(name.as_bytes());
-
-                    leb128::write::unsigned(&mut temp, params.len() as _).unwrap();
-                    temp.extend(params.iter().map(|&v| v as u8));
-                    leb128::write::unsigned(&mut temp, results.len() as _).unwrap();
-                    temp.extend(results.iter().map(|&v| v as u8));
+                    if let Some(class) = class {
+                        leb128::write::unsigned(&mut temp, 65).unwrap();
+                        write_str(&mut temp, class);
+                    } else if *is_async {
+                        leb128::write::unsigned(&mut temp, 69).unwrap();
+                    } else {
+                        leb128::write::unsigned(&mut temp, 64).unwrap();
+                    }
+                    write_str(&mut temp, name);
+                    args.write(&mut temp);
+                    write_str(&mut temp, doc);
                 }
                 SymbolType::ImportFunction(ImportFunction {
                     module,
                     name,
-                    args: FunctionArgs { params, results },
+                    args,
+                    doc,
                 }) => {
                     leb128::write::unsigned(&mut temp, 0).unwrap();
-                    leb128::write::unsigned(&mut temp, module.len() as _).unwrap();
-                    temp.extend_from_slice(module.as_bytes());
-                    leb128::write::unsigned(&mut temp, name.len() as _).unwrap();
-                    temp.extend_from_slice(name.as_bytes());
-
-                    leb128::write::unsigned(&mut temp, params.len() as _).unwrap();
-                    temp.extend(params.iter().map(|&v| v as u8));
-                    leb128::write::unsigned(&mut temp, results.len() as _).unwrap();
-                    temp.extend(results.iter().map(|&v| v as u8));
+                    write_str(&mut temp, module);
+                    write_str(&mut temp, name);
+                    args.write(&mut temp);
+                    write_str(&mut temp, doc);
+                }
+                SymbolType::ImportStatic(ImportFunction {
+                    module,
+                    name,
+                    args:
+                        FunctionArgs {
+                            results,
+                            result_types,
+                            ..
+                        },
+                    doc,
+                }) => {
+                    leb128::write::unsigned(&mut temp, 1).unwrap();
+                    write_str(&mut temp, module);
+                    write_str(&mut temp, name);
+                    write_arg(&mut temp, results[0], result_types[0].as_ref());
+                    write_str(&mut temp, doc);
+                }
+                SymbolType::ExportConst(ExportFunction {
+                    name,
+                    args:
+                        FunctionArgs {
+                            results,
+                            result_types,
+                            ..
+                        },
+                    doc,
+                    ..
+                }) => {
+                    leb128::write::unsigned(&mut temp, 68).unwrap();
+                    write_str(&mut temp, name);
+                    write_arg(&mut temp, results[0], result_types[0].as_ref());
+                    write_str(&mut temp, doc);
+                }
+                SymbolType::Class(Class {
+                    name,
+                    constructor,
+                    destructor,
+                    doc,
+                }) => {
+                    leb128::write::unsigned(&mut temp, 66).unwrap();
+                    write_str(&mut temp, name);
+                    write_str(&mut temp, constructor.as_deref().unwrap_or(""));
+                    write_str(&mut temp, destructor);
+                    write_str(&mut temp, doc);
+                }
+                SymbolType::Start(StartFunction { name }) => {
+                    leb128::write::unsigned(&mut temp, 67).unwrap();
+                    write_str(&mut temp, name);
+                }
+                SymbolType::Unknown { kind, data } => {
+                    leb128::write::unsigned(&mut temp, *kind).unwrap();
+                    temp.extend_from_slice(data);
                 }
             }
 
-            ret.extend_from_slice(version);
+            version.write(&mut ret);
             leb128::write::unsigned(&mut ret, temp.len() as _).unwrap();
             ret.extend_from_slice(&temp);
         }
@@ -160,7 +793,7 @@ impl TryFrom<&[u8]> for TargetFeatures {
     fn try_from(bytes: &[u8]) -> Result<Self, Error> {
         match parser::parse_target_features(bytes) {
             Ok((_, ret)) => Ok(ret),
-            Err(e) => bail!("{}", e),
+            Err(e) => Err(DecodeError::new("target_features", bytes, None, e).into()),
         }
     }
 }
@@ -170,7 +803,7 @@ impl CustomSection for TargetFeatures {
         "target_features"
     }
 
-    fn data(&self, _: &walrus::IdsToIndices) -> Cow<[u8]> {
+    fn data(&self, _: &walrus::IdsToIndices) -> Cow<'_, [u8]> {
         let mut ret = Vec::new();
         leb128::write::unsigned(&mut ret, self.features.len() as _).unwrap();
 
@@ -193,17 +826,149 @@ impl CustomSection for TargetFeatures {
 pub fn read_custom_data(
     module: &mut Module,
 ) -> Result<Option<TypedCustomSectionId<GodotWasmBindgenData>>, Error> {
+    let data = match take_custom_data(module)? {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    let mut custom_section = GodotWasmBindgenData::try_from(&data as &[_])?;
+    custom_section.merge_duplicates()?;
+    for s in &custom_section.symbols {
+        if let SymbolType::Unknown { kind, .. } = &s.inner {
+            eprintln!(
+                "warning: skipping symbol of unknown kind {} (version {})",
+                kind, s.version
+            );
+        }
+    }
+
+    Ok(Some(module.customs.add(custom_section)))
+}
+
+/// Reads bindgen metadata like [`read_custom_data`], but keeps what it rejects.
+///
+/// Symbols of incompatible version and conflicting duplicates are kept,
+/// and reported as notes instead.
+pub fn inspect_custom_data(
+    module: &mut Module,
+) -> Result<
+    (
+        Option<TypedCustomSectionId<GodotWasmBindgenData>>,
+        Vec<String>,
+    ),
+    Error,
+> {
+    let data = match take_custom_data(module)? {
+        Some(v) => v,
+        None => return Ok((None, Vec::new())),
+    };
+    let mut custom_section = GodotWasmBindgenData::decode_lenient(&data)?;
+
+    let mut notes: Vec<_> = custom_section
+        .symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.version.is_compatible())
+        .map(|(i, s)| {
+            format!(
+                "Symbol {} has version {}, which is incompatible with supported version {}.x",
+                i,
+                s.version,
+                Version::CURRENT.major
+            )
+        })
+        .collect();
+    notes.extend(
+        custom_section
+            .merge_identical()
+            .into_iter()
+            .map(|v| format!("Conflicting symbol definitions:\n{}", v)),
+    );
+
+    Ok((Some(module.customs.add(custom_section)), notes))
+}
+
+/// Takes the raw bindgen metadata out of module, parsing target features along the way.
+fn take_custom_data(module: &mut Module) -> Result<Option<Vec<u8>>, Error> {
     if let Some(data) = module.customs.remove_raw("target_features") {
         module
             .customs
             .add(TargetFeatures::try_from(&data.data as &[_])?);
     };
 
-    let data = match module.customs.remove_raw(GODOT_WASM_BINDGEN_NAME) {
-        Some(v) => v.data,
-        None => return Ok(None),
-    };
-    let custom_section = GodotWasmBindgenData::try_from(&data as &[_])?;
+    Ok(module
+        .customs
+        .remove_raw(GODOT_WASM_BINDGEN_NAME)
+        .map(|v| v.data))
+}
 
-    Ok(Some(module.customs.add(custom_section)))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(param: ArgType, doc: &str) -> Symbol {
+        Symbol {
+            version: Version::CURRENT,
+            inner: SymbolType::ExportFunction(ExportFunction {
+                class: None,
+                name: "f".into(),
+                args: FunctionArgs {
+                    params: vec![param],
+                    results: Vec::new(),
+                    param_names: vec!["a".into()],
+                    result_names: Vec::new(),
+                    optional: 0,
+                    param_types: vec![None],
+                    result_types: Vec::new(),
+                },
+                doc: doc.into(),
+                is_async: false,
+            }),
+        }
+    }
+
+    fn merge(symbols: Vec<Symbol>) -> Result<Vec<Symbol>, Error> {
+        let mut data = GodotWasmBindgenData { symbols };
+        data.merge_duplicates()?;
+        Ok(data.symbols)
+    }
+
+    #[test]
+    fn merge_identical_duplicates() {
+        let symbols = merge(vec![
+            function(ArgType::U32, "Doc."),
+            Symbol {
+                version: Version::CURRENT,
+                inner: SymbolType::Start(StartFunction { name: "s".into() }),
+            },
+            function(ArgType::U32, "Doc."),
+        ])
+        .unwrap();
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].inner, function(ArgType::U32, "Doc.").inner);
+        assert!(matches!(symbols[1].inner, SymbolType::Start(_)));
+    }
+
+    #[test]
+    fn merge_duplicates_differing_in_doc() {
+        let symbols = merge(vec![
+            function(ArgType::U32, "Doc."),
+            function(ArgType::U32, "Other doc."),
+        ])
+        .unwrap();
+
+        // The first definition is kept.
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].inner, function(ArgType::U32, "Doc.").inner);
+    }
+
+    #[test]
+    fn conflicting_duplicates() {
+        let e = merge(vec![function(ArgType::U32, ""), function(ArgType::I32, "")]).unwrap_err();
+
+        assert_eq!(
+            e.to_string(),
+            "Conflicting symbol definitions:\n  function f(a: U32) -> ()\n  function f(a: I32) -> ()"
+        );
+    }
 }
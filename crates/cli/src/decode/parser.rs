@@ -1,156 +1,454 @@
+use std::fmt::Display;
 use std::str::from_utf8;
 
 use anyhow::bail;
-use nom::bytes::complete::{tag, take, take_while_m_n};
-use nom::combinator::{all_consuming, fail, map, map_opt, map_res};
-use nom::multi::{length_count, length_data, length_value, many0};
+use nom::bytes::complete::take;
+use nom::combinator::{all_consuming, complete, fail, map, map_opt, map_res, rest, verify};
+use nom::error::{context, ContextError, ErrorKind, FromExternalError};
+use nom::multi::{length_count, length_data};
+use nom::number::complete::le_u16;
 use nom::sequence::{pair, tuple};
 use nom::{IResult, Parser};
 
 use super::{
-    ArgType, ExportFunction, Feature, FunctionArgs, GodotWasmBindgenData, ImportFunction, Symbol,
-    SymbolType, TargetFeatures,
+    ArgType, Class, ExportFunction, Feature, FunctionArgs, ImportFunction, PoolElement,
+    StartFunction, SymbolType, TargetFeatures, TypeInfo, ValueType, Version,
 };
 
-fn not_end_byte(b: u8) -> bool {
-    (b & 128) != 0
+type PResult<'a, T> = IResult<&'a [u8], T, ParseError<'a>>;
+
+/// Parser error, located at the innermost failing input.
+#[derive(Debug)]
+pub struct ParseError<'a> {
+    /// Remaining input where the error occurred.
+    pub input: &'a [u8],
+    /// Innermost context of the error.
+    pub expected: Option<&'static str>,
+    /// Underlying error of conversion, if any.
+    pub cause: Option<String>,
+    pub kind: ErrorKind,
 }
 
-fn leb128_unsigned(input: &[u8]) -> IResult<&[u8], u64> {
-    map_res(
-        pair(
-            take_while_m_n(0, 9, not_end_byte),
-            take::<usize, &[u8], _>(1),
-        ),
-        |(s, b)| {
-            debug_assert_eq!(b.len(), 1);
-            debug_assert!(!not_end_byte(b[0]), "Invalid end byte value {:02x}", b[0]);
-
-            let mut ret: u64 = (b[0] & 127).into();
-
-            for &x in s.iter().rev() {
-                debug_assert!(not_end_byte(x), "Invalid non-end byte value {x:02x}");
-                ret = match ret.checked_mul(128) {
-                    Some(v) => v | u64::from(x & 127),
-                    None => bail!("Value overflow!"),
-                };
-            }
+impl<'a> nom::error::ParseError<&'a [u8]> for ParseError<'a> {
+    fn from_error_kind(input: &'a [u8], kind: ErrorKind) -> Self {
+        Self {
+            input,
+            expected: None,
+            cause: None,
+            kind,
+        }
+    }
 
-            Ok(ret)
-        },
-    )(input)
+    fn append(_: &'a [u8], _: ErrorKind, other: Self) -> Self {
+        other
+    }
 }
 
-pub fn parse_bindgen_data(input: &[u8]) -> IResult<&[u8], GodotWasmBindgenData> {
-    map(all_consuming(many0(parse_symbol)), |symbols| {
-        GodotWasmBindgenData { symbols }
-    })(input)
+impl<'a> ContextError<&'a [u8]> for ParseError<'a> {
+    fn add_context(_: &'a [u8], ctx: &'static str, mut other: Self) -> Self {
+        other.expected.get_or_insert(ctx);
+        other
+    }
 }
 
-pub fn parse_symbol(input: &[u8]) -> IResult<&[u8], Symbol> {
-    fn parse_version(input: &[u8]) -> IResult<&[u8], [u8; 4]> {
-        let (input, v) = take(4usize)(input)?;
-        debug_assert_eq!(v.len(), 4);
-        Ok((input, v.try_into().unwrap()))
+impl<'a, E: Display> FromExternalError<&'a [u8], E> for ParseError<'a> {
+    fn from_external_error(input: &'a [u8], kind: ErrorKind, e: E) -> Self {
+        Self {
+            input,
+            expected: None,
+            cause: Some(e.to_string()),
+            kind,
+        }
     }
+}
 
-    map(
-        tag(&[1, 0, 0, 0]).and_then(parse_version).and(length_value(
-            leb128_unsigned,
-            all_consuming(parse_symbol_type),
-        )),
-        |(version, inner)| Symbol { version, inner },
+/// Unsigned LEB128 value, which must fit in 64 bits.
+fn leb128_unsigned(input: &[u8]) -> PResult<'_, u64> {
+    let mut ret = 0u64;
+    for (i, &b) in input.iter().enumerate() {
+        let v = u64::from(b & 127);
+        let shift = i * 7;
+        if shift >= 64 || (v << shift) >> shift != v {
+            return Err(nom::Err::Failure(ParseError {
+                cause: Some("LEB128 value overflows 64 bits".into()),
+                ..nom::error::ParseError::from_error_kind(&input[i..], ErrorKind::TooLarge)
+            }));
+        }
+        ret |= v << shift;
+
+        if b & 128 == 0 {
+            return Ok((&input[i + 1..], ret));
+        }
+    }
+
+    Err(nom::Err::Error(ParseError {
+        cause: Some("unterminated LEB128 value".into()),
+        ..nom::error::ParseError::from_error_kind(&input[input.len()..], ErrorKind::Eof)
+    }))
+}
+
+/// Length-prefixed bytes, which must be fully available.
+fn length_bytes(input: &[u8]) -> PResult<'_, &[u8]> {
+    complete(length_data(leb128_unsigned))(input)
+}
+
+/// Symbol of any version is length-prefixed, so it can be skipped.
+///
+/// It's content is parsed by [`parse_symbol_type`].
+pub fn parse_symbol(input: &[u8]) -> PResult<'_, (Version, &[u8])> {
+    pair(
+        context("symbol version", parse_version),
+        context("symbol data", length_bytes),
     )(input)
 }
 
-pub fn parse_symbol_type(input: &[u8]) -> IResult<&[u8], SymbolType> {
-    fn switch_symbol(v: u64) -> impl Fn(&[u8]) -> IResult<&[u8], SymbolType> {
+fn parse_version(input: &[u8]) -> PResult<'_, Version> {
+    map(pair(le_u16, le_u16), |(major, minor)| Version {
+        major,
+        minor,
+    })(input)
+}
+
+/// Parses content of a symbol, unknown kind is kept as is.
+pub fn parse_symbol_type(input: &[u8]) -> PResult<'_, SymbolType> {
+    fn switch_symbol(v: u64) -> impl Fn(&[u8]) -> PResult<'_, SymbolType> {
         move |i| match v {
-            64 => map(parse_export_function, |v| SymbolType::ExportFunction(v))(i),
-            0 => map(parse_import_function, |v| SymbolType::ImportFunction(v))(i),
-            _ => fail(i),
+            64 => map(parse_export_function, SymbolType::ExportFunction)(i),
+            65 => map(parse_export_method, SymbolType::ExportFunction)(i),
+            66 => map(parse_class, SymbolType::Class)(i),
+            67 => map(parse_start, SymbolType::Start)(i),
+            68 => map(parse_export_const, SymbolType::ExportConst)(i),
+            69 => map(parse_export_async, SymbolType::ExportFunction)(i),
+            0 => map(parse_import_function, SymbolType::ImportFunction)(i),
+            1 => map(parse_import_static, SymbolType::ImportStatic)(i),
+            kind => map(rest, |data: &[u8]| SymbolType::Unknown {
+                kind,
+                data: data.into(),
+            })(i),
         }
     }
 
-    leb128_unsigned.flat_map(switch_symbol).parse(input)
+    context(
+        "end of symbol",
+        all_consuming(context("symbol kind", leb128_unsigned).flat_map(switch_symbol)),
+    )
+    .parse(input)
+}
+
+/// Parses content of a symbol of incompatible version, which is kept as is.
+pub fn parse_unknown_symbol(input: &[u8]) -> PResult<'_, SymbolType> {
+    map(
+        pair(context("symbol kind", leb128_unsigned), rest),
+        |(kind, data): (u64, &[u8])| SymbolType::Unknown {
+            kind,
+            data: data.into(),
+        },
+    )(input)
+}
+
+fn parse_string(input: &[u8]) -> PResult<'_, &str> {
+    context("UTF-8 string", map_res(length_bytes, from_utf8))(input)
 }
 
-pub fn parse_export_function(input: &[u8]) -> IResult<&[u8], ExportFunction> {
+pub fn parse_export_function(input: &[u8]) -> PResult<'_, ExportFunction> {
+    map(
+        tuple((parse_string, parse_function_args, parse_string)),
+        |(name, args, doc)| ExportFunction {
+            class: None,
+            name: name.into(),
+            args,
+            doc: doc.into(),
+            is_async: false,
+        },
+    )(input)
+}
+
+pub fn parse_export_async(input: &[u8]) -> PResult<'_, ExportFunction> {
+    map(parse_export_function, |v| ExportFunction {
+        is_async: true,
+        ..v
+    })(input)
+}
+
+pub fn parse_export_method(input: &[u8]) -> PResult<'_, ExportFunction> {
     map(
         tuple((
-            map_res(length_data(leb128_unsigned), from_utf8),
+            parse_string,
+            parse_string,
             parse_function_args,
+            parse_string,
         )),
-        |(name, args)| ExportFunction {
+        |(class, name, args, doc)| ExportFunction {
+            class: Some(class.into()),
             name: name.into(),
             args,
+            doc: doc.into(),
+            is_async: false,
+        },
+    )(input)
+}
+
+/// Constant is exported as getter function without parameters.
+pub fn parse_export_const(input: &[u8]) -> PResult<'_, ExportFunction> {
+    map(
+        tuple((parse_string, parse_arg, parse_string)),
+        |(name, (ty, info), doc)| ExportFunction {
+            class: None,
+            name: name.into(),
+            args: FunctionArgs {
+                params: Vec::new(),
+                results: vec![ty],
+                param_names: Vec::new(),
+                result_names: vec![name.into()],
+                optional: 0,
+                param_types: Vec::new(),
+                result_types: vec![info],
+            },
+            doc: doc.into(),
+            is_async: false,
         },
     )(input)
 }
 
-pub fn parse_import_function(input: &[u8]) -> IResult<&[u8], ImportFunction> {
+pub fn parse_class(input: &[u8]) -> PResult<'_, Class> {
+    map(
+        tuple((parse_string, parse_string, parse_string, parse_string)),
+        |(name, constructor, destructor, doc)| Class {
+            name: name.into(),
+            constructor: match constructor {
+                "" => None,
+                v => Some(v.into()),
+            },
+            destructor: destructor.into(),
+            doc: doc.into(),
+        },
+    )(input)
+}
+
+pub fn parse_start(input: &[u8]) -> PResult<'_, StartFunction> {
+    map(parse_string, |name| StartFunction { name: name.into() })(input)
+}
+
+pub fn parse_import_function(input: &[u8]) -> PResult<'_, ImportFunction> {
     map(
         tuple((
-            map_res(length_data(leb128_unsigned), from_utf8),
-            map_res(length_data(leb128_unsigned), from_utf8),
+            parse_string,
+            parse_string,
             parse_function_args,
+            parse_string,
         )),
-        |(module, name, args)| ImportFunction {
+        |(module, name, args, doc)| ImportFunction {
             module: module.into(),
             name: name.into(),
             args,
+            doc: doc.into(),
         },
     )(input)
 }
 
-pub fn parse_function_args(input: &[u8]) -> IResult<&[u8], FunctionArgs> {
-    fn arg_type(v: &[u8]) -> Option<ArgType> {
-        debug_assert_eq!(v.len(), 1);
+fn arg_type(v: &[u8]) -> Option<ArgType> {
+    match *v.first()? {
+        1 => Some(ArgType::U8),
+        2 => Some(ArgType::I8),
+        3 => Some(ArgType::U16),
+        4 => Some(ArgType::I16),
+        5 => Some(ArgType::U32),
+        6 => Some(ArgType::I32),
+        7 => Some(ArgType::U64),
+        8 => Some(ArgType::I64),
+        9 => Some(ArgType::F32),
+        10 => Some(ArgType::F64),
+        11 => Some(ArgType::GodotValue),
+        12 => Some(ArgType::Handle),
+        13 => Some(ArgType::GodotValueRef),
+        14 => Some(ArgType::String),
+        15 => Some(ArgType::ByteArray),
+        16 => Some(ArgType::IntArray),
+        17 => Some(ArgType::FloatArray),
+        18 => Some(ArgType::Vector2Array),
+        19 => Some(ArgType::Vector3Array),
+        20 => Some(ArgType::ColorArray),
+        _ => None,
+    }
+}
+
+/// Parses argument type, `GodotValue` is followed by it's value type.
+fn parse_arg(input: &[u8]) -> PResult<'_, (ArgType, Option<TypeInfo>)> {
+    let (input, ty) = context("argument type", map_opt(take(1usize), arg_type))(input)?;
+    match ty {
+        ArgType::GodotValue => map(parse_type_info, |v| (ty, Some(v)))(input),
+        _ => Ok((input, (ty, None))),
+    }
+}
+
+fn value_type(v: &[u8]) -> Option<ValueType> {
+    match *v.first()? {
+        1 => Some(ValueType::Bool),
+        2 => Some(ValueType::Int),
+        3 => Some(ValueType::Float),
+        4 => Some(ValueType::String),
+        5 => Some(ValueType::Vector2),
+        6 => Some(ValueType::Rect2),
+        7 => Some(ValueType::Vector3),
+        8 => Some(ValueType::Transform2D),
+        9 => Some(ValueType::Plane),
+        10 => Some(ValueType::Quat),
+        11 => Some(ValueType::Aabb),
+        12 => Some(ValueType::Basis),
+        13 => Some(ValueType::Transform),
+        14 => Some(ValueType::Color),
+        15 => Some(ValueType::NodePath),
+        16 => Some(ValueType::Rid),
+        17 => Some(ValueType::Object),
+        18 => Some(ValueType::Dictionary),
+        19 => Some(ValueType::Array),
+        20 => Some(ValueType::ByteArray),
+        21 => Some(ValueType::IntArray),
+        22 => Some(ValueType::FloatArray),
+        23 => Some(ValueType::StringArray),
+        24 => Some(ValueType::Vector2Array),
+        25 => Some(ValueType::Vector3Array),
+        26 => Some(ValueType::ColorArray),
+        _ => None,
+    }
+}
+
+fn pool_element(v: &[u8]) -> Option<PoolElement> {
+    match *v.first()? {
+        1 => Some(PoolElement::Byte),
+        2 => Some(PoolElement::Int),
+        3 => Some(PoolElement::Float),
+        4 => Some(PoolElement::String),
+        5 => Some(PoolElement::Vector2),
+        6 => Some(PoolElement::Vector3),
+        7 => Some(PoolElement::Color),
+        _ => None,
+    }
+}
+
+/// Maximum nesting of type descriptor, so malformed data cannot overflow the stack.
+const MAX_TYPE_DEPTH: usize = 64;
+
+pub fn parse_type_info(input: &[u8]) -> PResult<'_, TypeInfo> {
+    type_info_at(input, 0)
+}
+
+fn type_info_at<'a>(input: &'a [u8], depth: usize) -> PResult<'a, TypeInfo> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err(nom::Err::Failure(ParseError {
+            expected: Some("type descriptor"),
+            cause: Some(format!("nested deeper than {} levels", MAX_TYPE_DEPTH)),
+            ..nom::error::ParseError::from_error_kind(input, ErrorKind::TooLarge)
+        }));
+    }
+
+    let inner = move |i: &'a [u8]| type_info_at(i, depth + 1);
+    let switch_type = move |input: &'a [u8]| {
+        let (i, v) = take(1usize)(input)?;
         match v[0] {
-            1 => Some(ArgType::U8),
-            2 => Some(ArgType::I8),
-            3 => Some(ArgType::U16),
-            4 => Some(ArgType::I16),
-            5 => Some(ArgType::U32),
-            6 => Some(ArgType::I32),
-            7 => Some(ArgType::U64),
-            8 => Some(ArgType::I64),
-            9 => Some(ArgType::F32),
-            10 => Some(ArgType::F64),
-            11 => Some(ArgType::GodotValue),
-            _ => None,
+            0 => Ok((i, TypeInfo::Any)),
+            1 => map(
+                context("value type", map_opt(take(1usize), value_type)),
+                TypeInfo::Value,
+            )(i),
+            2 => map(inner, |v| TypeInfo::Optional(Box::new(v)))(i),
+            3 => map(inner, |v| TypeInfo::Array(Box::new(v)))(i),
+            4 => map(length_count(leb128_unsigned, inner), TypeInfo::Tuple)(i),
+            5 => map(
+                length_count(
+                    leb128_unsigned,
+                    pair(map(parse_string, String::from), inner),
+                ),
+                TypeInfo::Struct,
+            )(i),
+            6 => map(
+                context("pool element type", map_opt(take(1usize), pool_element)),
+                TypeInfo::PoolArray,
+            )(i),
+            // Located at the tag itself.
+            _ => fail(input),
         }
-    }
+    };
+
+    context("type descriptor", switch_type)(input)
+}
 
+/// Static is imported as getter function without parameters.
+pub fn parse_import_static(input: &[u8]) -> PResult<'_, ImportFunction> {
     map(
-        tuple((
-            length_value(leb128_unsigned, many0(map_opt(take(1usize), arg_type))),
-            length_value(leb128_unsigned, many0(map_opt(take(1usize), arg_type))),
-        )),
-        |(params, results)| FunctionArgs { params, results },
+        tuple((parse_string, parse_string, parse_arg, parse_string)),
+        |(module, name, (ty, info), doc)| ImportFunction {
+            module: module.into(),
+            name: name.into(),
+            args: FunctionArgs {
+                params: Vec::new(),
+                results: vec![ty],
+                param_names: Vec::new(),
+                result_names: vec![name.into()],
+                optional: 0,
+                param_types: Vec::new(),
+                result_types: vec![info],
+            },
+            doc: doc.into(),
+        },
+    )(input)
+}
+
+pub fn parse_function_args(input: &[u8]) -> PResult<'_, FunctionArgs> {
+    fn names(input: &[u8]) -> PResult<'_, Vec<String>> {
+        length_count(leb128_unsigned, map(parse_string, String::from))(input)
+    }
+
+    context(
+        "function arguments",
+        verify(
+            map(
+                tuple((
+                    length_count(leb128_unsigned, parse_arg),
+                    length_count(leb128_unsigned, parse_arg),
+                    names,
+                    names,
+                    map_res(leb128_unsigned, usize::try_from),
+                )),
+                |(params, results, param_names, result_names, optional)| {
+                    let (params, param_types) = params.into_iter().unzip();
+                    let (results, result_types) = results.into_iter().unzip();
+                    FunctionArgs {
+                        params,
+                        results,
+                        param_names,
+                        result_names,
+                        optional,
+                        param_types,
+                        result_types,
+                    }
+                },
+            ),
+            |v| {
+                v.params.len() == v.param_names.len()
+                    && v.results.len() == v.result_names.len()
+                    && v.optional <= v.params.len()
+            },
+        ),
     )(input)
 }
 
-pub fn parse_target_features(input: &[u8]) -> IResult<&[u8], TargetFeatures> {
+pub fn parse_target_features(input: &[u8]) -> PResult<'_, TargetFeatures> {
     map(
         all_consuming(length_count(leb128_unsigned, parse_feature)),
         |features| TargetFeatures { features },
     )(input)
 }
 
-pub fn parse_feature(input: &[u8]) -> IResult<&[u8], Feature> {
+pub fn parse_feature(input: &[u8]) -> PResult<'_, Feature> {
     map(
         tuple((
-            map_res(take(1usize), |e: &[u8]| {
-                debug_assert_eq!(e.len(), 1);
-
-                match e[0] {
-                    43 => Ok(true),
-                    45 => Ok(false),
-                    v => bail!("Unknown flag {}", char::from_u32(v.into()).unwrap_or('?')),
-                }
+            map_res(take(1usize), |e: &[u8]| match e[0] {
+                43 => Ok(true),
+                45 => Ok(false),
+                v => bail!("Unknown flag {}", char::from_u32(v.into()).unwrap_or('?')),
             }),
-            map_res(length_data(leb128_unsigned), from_utf8),
+            parse_string,
         )),
         |(enabled, name)| Feature {
             enabled,
@@ -158,3 +456,173 @@ pub fn parse_feature(input: &[u8]) -> IResult<&[u8], Feature> {
         },
     )(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{DecodeError, GodotWasmBindgenData, SymbolType, Version};
+
+    const VERSION: [u8; 4] = [2, 0, 0, 0];
+
+    fn decode_error(data: &[u8]) -> DecodeError {
+        match GodotWasmBindgenData::try_from(data) {
+            Ok(v) => panic!("decoded malformed data: {:?}", v),
+            Err(e) => match e.downcast::<DecodeError>() {
+                Ok(e) => e,
+                Err(e) => panic!("error is not located: {}", e),
+            },
+        }
+    }
+
+    /// Start function symbol named `s`, 8 bytes long.
+    fn start_symbol() -> Vec<u8> {
+        let mut ret = VERSION.to_vec();
+        ret.extend([3, 67, 1, b's']);
+        ret
+    }
+
+    #[test]
+    fn unterminated_leb128() {
+        let mut data = start_symbol();
+        data.extend(VERSION);
+        data.extend([0x80, 0x80]);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, 14);
+        assert_eq!(e.symbol, Some(1));
+        assert_eq!(e.expected, Some("symbol data"));
+        assert_eq!(e.cause.as_deref(), Some("unterminated LEB128 value"));
+    }
+
+    #[test]
+    fn overflowing_leb128() {
+        let mut data = VERSION.to_vec();
+        data.extend([0xff; 10]);
+        data.push(0x01);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, 13);
+        assert_eq!(e.symbol, Some(0));
+        assert_eq!(e.cause.as_deref(), Some("LEB128 value overflows 64 bits"));
+    }
+
+    #[test]
+    fn truncated_length_prefix() {
+        let mut data = start_symbol();
+        data.extend(VERSION);
+        data.extend([10, 67, 1]);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, 12);
+        assert_eq!(e.symbol, Some(1));
+        assert_eq!(e.expected, Some("symbol data"));
+        assert_eq!(e.cause.as_deref(), Some("unexpected end of data"));
+    }
+
+    #[test]
+    fn truncated_version() {
+        let mut data = start_symbol();
+        data.extend([2, 0]);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, 10);
+        assert_eq!(e.symbol, Some(1));
+        assert_eq!(e.expected, Some("symbol version"));
+        assert_eq!(e.cause.as_deref(), Some("unexpected end of data"));
+    }
+
+    #[test]
+    fn bad_type_tag() {
+        // Function `f` with a single value parameter of unknown type tag 9.
+        let mut data = start_symbol();
+        data.extend(VERSION);
+        data.extend([10, 64, 1, b'f', 1, 11, 9, 0, 0, 0, 0]);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, 18);
+        assert_eq!(e.symbol, Some(1));
+        assert_eq!(e.expected, Some("type descriptor"));
+    }
+
+    #[test]
+    fn bad_symbol_string() {
+        let mut data = VERSION.to_vec();
+        data.extend([3, 67, 1, 0xff]);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, 6);
+        assert_eq!(e.symbol, Some(0));
+        assert_eq!(e.expected, Some("UTF-8 string"));
+    }
+
+    #[test]
+    fn nested_type_descriptor() {
+        // Optional nested far beyond the limit.
+        let mut body = vec![64, 1, b'f', 1, 11];
+        body.extend([2; 1000]);
+        body.extend([0, 0, 0, 0, 0]);
+
+        let mut data = VERSION.to_vec();
+        leb128::write::unsigned(&mut data, body.len() as _).unwrap();
+        let start = data.len();
+        data.extend(body);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, start + 5 + super::MAX_TYPE_DEPTH + 1);
+        assert_eq!(e.symbol, Some(0));
+        assert_eq!(e.expected, Some("type descriptor"));
+    }
+
+    #[test]
+    fn incompatible_version() {
+        let mut data = start_symbol();
+        data.extend([1, 0, 0, 0, 3, 67, 1, b's']);
+
+        let e = decode_error(&data);
+        assert_eq!(e.offset, 8);
+        assert_eq!(e.symbol, Some(1));
+        assert_eq!(e.expected, Some("compatible symbol version"));
+        assert!(e
+            .cause
+            .unwrap()
+            .starts_with("version 1.0 is incompatible with supported version 2.x"));
+    }
+
+    #[test]
+    fn incompatible_version_lenient() {
+        let mut data = start_symbol();
+        data.extend([1, 0, 0, 0, 3, 67, 1, b's']);
+
+        let symbols = GodotWasmBindgenData::decode_lenient(&data)
+            .expect("incompatible symbol is kept")
+            .symbols;
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[1].version, Version { major: 1, minor: 0 });
+        assert_eq!(
+            symbols[1].inner,
+            SymbolType::Unknown {
+                kind: 67,
+                data: vec![1, b's'],
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_symbol_kind() {
+        // Newer minor version, adding a symbol kind.
+        let mut data = start_symbol();
+        data.extend([2, 0, 1, 0, 3, 99, 1, 2]);
+
+        let symbols = GodotWasmBindgenData::try_from(&data as &[_])
+            .expect("unknown symbol kind is skipped")
+            .symbols;
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[1].version, Version { major: 2, minor: 1 });
+        assert_eq!(
+            symbols[1].inner,
+            SymbolType::Unknown {
+                kind: 99,
+                data: vec![1, 2],
+            }
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt::Write as _;
+
+use anyhow::Error;
+use serde_json::{json, Value};
+use walrus::{ExportItem, FunctionId, ImportKind, Module, TypedCustomSectionId, ValType};
+
+use crate::decode::{
+    inspect_custom_data, ArgType, FunctionArgs, GodotWasmBindgenData, SymbolType, TargetFeatures,
+    TypeInfo, Version,
+};
+
+/// Prints bindgen interface of the module, as text or JSON.
+///
+/// Metadata that would fail the build is still printed, with notes on the problems.
+pub fn inspect(module: &mut Module, as_json: bool) -> Result<(), Error> {
+    let (custom_id, notes) = inspect_custom_data(module)?;
+    let module = &*module;
+
+    let output = if as_json {
+        serde_json::to_string_pretty(&inspect_json(module, custom_id, &notes))?
+    } else {
+        inspect_text(module, custom_id, &notes)
+    };
+    println!("{}", output);
+
+    Ok(())
+}
+
+type CustomId = Option<TypedCustomSectionId<GodotWasmBindgenData>>;
+
+/// Section of text output listing the symbol.
+fn section(symbol: &SymbolType) -> &'static str {
+    match symbol {
+        SymbolType::Class(_) => "Classes",
+        SymbolType::ExportFunction(_) | SymbolType::ExportConst(_) => "Exports",
+        SymbolType::ImportFunction(_) | SymbolType::ImportStatic(_) => "Imports",
+        SymbolType::Start(_) => "Start",
+        SymbolType::Unknown { .. } => "Unknown",
+    }
+}
+
+fn inspect_text(module: &Module, custom_id: CustomId, notes: &[String]) -> String {
+    let mut ret = String::new();
+
+    if let Some(features) = module.customs.get_typed::<TargetFeatures>() {
+        let features: Vec<_> = features
+            .features
+            .iter()
+            .map(|f| format!("{}{}", if f.enabled { '+' } else { '-' }, f.name))
+            .collect();
+        writeln!(ret, "Target features: {}", features.join(", ")).unwrap();
+    }
+
+    let data = match custom_id.and_then(|id| module.customs.get(id)) {
+        Some(v) => v,
+        None => {
+            ret.push_str("No godot-wasm-bindgen metadata");
+            return ret;
+        }
+    };
+
+    for title in ["Classes", "Exports", "Imports", "Start", "Unknown"] {
+        let mut symbols = data
+            .symbols
+            .iter()
+            .filter(|s| section(&s.inner) == title)
+            .peekable();
+        if symbols.peek().is_none() {
+            continue;
+        }
+
+        writeln!(ret, "\n{}:", title).unwrap();
+        for s in symbols {
+            write!(ret, "  {}", s.inner).unwrap();
+            if s.version != Version::CURRENT {
+                write!(ret, " (version {})", s.version).unwrap();
+            }
+            ret.push('\n');
+
+            if let Some(f) = wasm_function(module, &s.inner) {
+                let ty = module.types.get(module.funcs.get(f).ty());
+                let join = |v: &[ValType]| {
+                    v.iter()
+                        .map(|t| t.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                writeln!(
+                    ret,
+                    "    wasm: ({}) -> ({})",
+                    join(ty.params()),
+                    join(ty.results())
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    if !notes.is_empty() {
+        ret.push_str("\nNotes:\n");
+        for line in notes.iter().flat_map(|v| v.lines()) {
+            writeln!(ret, "  {}", line).unwrap();
+        }
+    }
+
+    ret.truncate(ret.trim_end().len());
+    ret
+}
+
+fn inspect_json(module: &Module, custom_id: CustomId, notes: &[String]) -> Value {
+    let features = module.customs.get_typed::<TargetFeatures>().map(|v| {
+        v.features
+            .iter()
+            .map(|f| json!({ "name": f.name, "enabled": f.enabled }))
+            .collect::<Vec<_>>()
+    });
+
+    let symbols = custom_id.and_then(|id| module.customs.get(id)).map(|data| {
+        data.symbols
+            .iter()
+            .map(|s| {
+                let mut ret = symbol_json(&s.inner);
+                ret["version"] = json!(s.version.to_string());
+                ret["wasm"] = match wasm_function(module, &s.inner) {
+                    Some(f) => {
+                        let ty = module.types.get(module.funcs.get(f).ty());
+                        let map =
+                            |v: &[ValType]| v.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+                        json!({ "params": map(ty.params()), "results": map(ty.results()) })
+                    }
+                    None => Value::Null,
+                };
+                ret
+            })
+            .collect::<Vec<_>>()
+    });
+
+    json!({
+        "target_features": features,
+        "symbols": symbols,
+        "notes": notes,
+    })
+}
+
+fn symbol_json(symbol: &SymbolType) -> Value {
+    match symbol {
+        SymbolType::ExportFunction(v) => {
+            let kind = match (&v.class, v.is_async) {
+                (Some(_), _) => "method",
+                (None, true) => "async_function",
+                (None, false) => "function",
+            };
+            let mut ret = json!({
+                "kind": kind,
+                "name": v.name,
+                "doc": v.doc,
+            });
+            if let Some(c) = &v.class {
+                ret["class"] = json!(c);
+            }
+            args_json(&mut ret, &v.args);
+            ret
+        }
+        SymbolType::ExportConst(v) => {
+            let mut ret = json!({ "kind": "constant", "name": v.name, "doc": v.doc });
+            args_json(&mut ret, &v.args);
+            ret
+        }
+        SymbolType::ImportFunction(v) | SymbolType::ImportStatic(v) => {
+            let kind = match symbol {
+                SymbolType::ImportStatic(_) => "static",
+                _ => "import",
+            };
+            let mut ret = json!({
+                "kind": kind,
+                "module": v.module,
+                "name": v.name,
+                "doc": v.doc,
+            });
+            args_json(&mut ret, &v.args);
+            ret
+        }
+        SymbolType::Class(v) => json!({
+            "kind": "class",
+            "name": v.name,
+            "constructor": v.constructor,
+            "destructor": v.destructor,
+            "doc": v.doc,
+        }),
+        SymbolType::Start(v) => json!({ "kind": "start", "name": v.name }),
+        SymbolType::Unknown { kind, data } => json!({
+            "kind": "unknown",
+            "id": kind,
+            "size": data.len(),
+        }),
+    }
+}
+
+fn args_json(value: &mut Value, args: &FunctionArgs) {
+    fn map<'a>(
+        names: &'a [String],
+        args: &'a [ArgType],
+        types: &'a [Option<TypeInfo>],
+    ) -> Vec<Value> {
+        names
+            .iter()
+            .zip(args)
+            .zip(types)
+            .map(|((n, a), t)| {
+                json!({
+                    "name": n,
+                    "type": format!("{:?}", a),
+                    "value_type": t.as_ref().map(type_json),
+                })
+            })
+            .collect()
+    }
+
+    value["params"] = map(&args.param_names, &args.params, &args.param_types).into();
+    value["results"] = map(&args.result_names, &args.results, &args.result_types).into();
+    value["optional"] = args.optional.into();
+}
+
+fn type_json(ty: &TypeInfo) -> Value {
+    match ty {
+        TypeInfo::Any => json!("Variant"),
+        TypeInfo::Value(v) => json!(format!("{:?}", v)),
+        TypeInfo::Optional(v) => json!({ "optional": type_json(v) }),
+        TypeInfo::Array(v) => json!({ "array": type_json(v) }),
+        TypeInfo::Tuple(v) => json!({ "tuple": v.iter().map(type_json).collect::<Vec<_>>() }),
+        TypeInfo::Struct(v) => json!({
+            "struct": v
+                .iter()
+                .map(|(n, t)| json!({ "name": n, "type": type_json(t) }))
+                .collect::<Vec<_>>(),
+        }),
+        TypeInfo::PoolArray(v) => json!({ "pool_array": format!("{:?}", v) }),
+    }
+}
+
+/// Gets wasm function implementing the symbol, if any.
+fn wasm_function(module: &Module, symbol: &SymbolType) -> Option<FunctionId> {
+    let name = match symbol {
+        SymbolType::ExportFunction(v) | SymbolType::ExportConst(v) => &v.name,
+        SymbolType::Start(v) => &v.name,
+        SymbolType::ImportFunction(v) | SymbolType::ImportStatic(v) => {
+            let id = module.imports.find(&v.module, &v.name)?;
+            return match module.imports.get(id).kind {
+                ImportKind::Function(f) => Some(f),
+                _ => None,
+            };
+        }
+        SymbolType::Class(_) | SymbolType::Unknown { .. } => return None,
+    };
+
+    module.exports.iter().find_map(|e| match e.item {
+        ExportItem::Function(f) if e.name == *name => Some(f),
+        _ => None,
+    })
+}
@@ -1,4 +1,5 @@
 mod decode;
+mod inspect;
 mod runtime;
 mod substitution;
 mod util;
@@ -6,62 +7,72 @@ mod util;
 use std::path::PathBuf;
 
 use anyhow::Error;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use walrus::passes::gc;
-use walrus::{FunctionKind, Module};
+use walrus::Module;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about)]
+#[command(
+    author,
+    version,
+    about,
+    long_about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long, help = "Output file name")]
     output: Option<PathBuf>,
 
-    #[arg(help = "Input file name")]
-    file: PathBuf,
+    #[arg(required = true, help = "Input file name")]
+    file: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[command(about = "Print bindgen interface of a module")]
+    Inspect {
+        #[arg(long, help = "Print as JSON")]
+        json: bool,
+
+        #[arg(help = "Input file name")]
+        file: PathBuf,
+    },
 }
 
 fn main() -> Result<(), Error> {
     let args = Args::parse();
 
-    let mut module = Module::from_file(args.file)?;
+    match args.command {
+        Some(Command::Inspect { json, file }) => {
+            inspect::inspect(&mut Module::from_file(file)?, json)
+        }
+        None => match args.file {
+            Some(file) => build(file, args.output),
+            None => unreachable!("input file is required"),
+        },
+    }
+}
+
+fn build(file: PathBuf, output: Option<PathBuf>) -> Result<(), Error> {
+    let mut module = Module::from_file(file)?;
 
     let custom_id = decode::read_custom_data(&mut module)?;
-    println!("{:?}", custom_id.and_then(|v| module.customs.get(v)));
 
     let runtime = runtime::add_runtime(&mut module)?;
 
-    if let Some(custom_data) = custom_id.and_then(|id| module.customs.delete(id)) {
+    if let Some(mut custom_data) = custom_id.and_then(|id| module.customs.delete(id)) {
+        substitution::strip_executor(&mut module, &mut custom_data);
         substitution::substitute_exports(&mut module, &custom_data, &runtime)?;
         substitution::substitute_imports(&mut module, &custom_data, &runtime)?;
+        substitution::substitute_start(&mut module, &custom_data)?;
     }
 
     gc::run(&mut module);
 
-    for f in module.funcs.iter() {
-        if let FunctionKind::Import(v) = &f.kind {
-            println!(
-                "{:?} {}: {:?} import {:?}",
-                f.id(),
-                match f.name.as_ref() {
-                    Some(v) => v,
-                    None => "",
-                },
-                module.types.get(v.ty),
-                module.imports.get(v.import),
-            );
-        } else {
-            println!(
-                "{:?} {}: {:?} local",
-                f.id(),
-                match f.name.as_ref() {
-                    Some(v) => v,
-                    None => "",
-                },
-                module.types.get(f.ty()),
-            );
-        }
-    }
-
     if let Some(features) = module.customs.get_typed_mut::<decode::TargetFeatures>() {
         features.features.push(decode::Feature {
             enabled: true,
@@ -69,7 +80,7 @@ fn main() -> Result<(), Error> {
         });
     }
 
-    if let Some(output) = &args.output {
+    if let Some(output) = &output {
         module.emit_wasm_file(output)?;
     }
 
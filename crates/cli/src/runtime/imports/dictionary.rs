@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use walrus::{FunctionBuilder, FunctionId, Module, ValType};
+
+use super::{replace_import, EXTERNREF_MODULE};
+use crate::runtime::RuntimeData;
+
+pub fn generate_imports(
+    module: &mut Module,
+    func_map: &mut HashMap<FunctionId, FunctionId>,
+    runtime: &RuntimeData,
+) -> Result<(), Error> {
+    replace_import(&mut *module, &mut *func_map, "dictionary.new", |module| {
+        let ty = module.types.add(&[], &[ValType::Externref]);
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.new", ty);
+        let mut builder = FunctionBuilder::new(&mut module.types, &[], &[ValType::I32]);
+
+        builder.name(String::from("godot_wasm.dictionary.new"));
+
+        let RuntimeData { alloc_func, .. } = *runtime;
+
+        builder.func_body().call(import_func).call(alloc_func);
+
+        Ok(builder.finish(vec![], &mut module.funcs))
+    })?;
+
+    replace_import(&mut *module, &mut *func_map, "dictionary.len", |module| {
+        let ty = module.types.add(&[ValType::Externref], &[ValType::I32]);
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.len", ty);
+        let mut builder = FunctionBuilder::new(&mut module.types, &[ValType::I32], &[ValType::I32]);
+
+        builder.name(String::from("godot_wasm.dictionary.len"));
+
+        let RuntimeData { get_func, .. } = *runtime;
+
+        let o = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(o)
+            .call(get_func)
+            .call(import_func);
+
+        Ok(builder.finish(vec![o], &mut module.funcs))
+    })?;
+
+    replace_import(&mut *module, &mut *func_map, "dictionary.has", |module| {
+        let ty = module
+            .types
+            .add(&[ValType::Externref, ValType::Externref], &[ValType::I32]);
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.has", ty);
+        let mut builder = FunctionBuilder::new(
+            &mut module.types,
+            &[ValType::I32, ValType::I32],
+            &[ValType::I32],
+        );
+
+        builder.name(String::from("godot_wasm.dictionary.has"));
+
+        let RuntimeData { get_func, .. } = *runtime;
+
+        let o = module.locals.add(ValType::I32);
+        let k = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(o)
+            .call(get_func)
+            .local_get(k)
+            .call(get_func)
+            .call(import_func);
+
+        Ok(builder.finish(vec![o, k], &mut module.funcs))
+    })?;
+
+    replace_import(&mut *module, &mut *func_map, "dictionary.get", |module| {
+        let ty = module.types.add(
+            &[ValType::Externref, ValType::Externref],
+            &[ValType::Externref],
+        );
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.get", ty);
+        let mut builder = FunctionBuilder::new(
+            &mut module.types,
+            &[ValType::I32, ValType::I32],
+            &[ValType::I32],
+        );
+
+        builder.name(String::from("godot_wasm.dictionary.get"));
+
+        let RuntimeData {
+            get_func,
+            alloc_func,
+            ..
+        } = *runtime;
+
+        let o = module.locals.add(ValType::I32);
+        let k = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(o)
+            .call(get_func)
+            .local_get(k)
+            .call(get_func)
+            .call(import_func)
+            .call(alloc_func);
+
+        Ok(builder.finish(vec![o, k], &mut module.funcs))
+    })?;
+
+    replace_import(&mut *module, &mut *func_map, "dictionary.set", |module| {
+        let ty = module.types.add(
+            &[ValType::Externref, ValType::Externref, ValType::Externref],
+            &[],
+        );
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.set", ty);
+        let mut builder = FunctionBuilder::new(
+            &mut module.types,
+            &[ValType::I32, ValType::I32, ValType::I32],
+            &[],
+        );
+
+        builder.name(String::from("godot_wasm.dictionary.set"));
+
+        let RuntimeData { get_func, .. } = *runtime;
+
+        let o = module.locals.add(ValType::I32);
+        let k = module.locals.add(ValType::I32);
+        let v = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(o)
+            .call(get_func)
+            .local_get(k)
+            .call(get_func)
+            .local_get(v)
+            .call(get_func)
+            .call(import_func);
+
+        Ok(builder.finish(vec![o, k, v], &mut module.funcs))
+    })?;
+
+    replace_import(&mut *module, &mut *func_map, "dictionary.erase", |module| {
+        let ty = module
+            .types
+            .add(&[ValType::Externref, ValType::Externref], &[ValType::I32]);
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.erase", ty);
+        let mut builder = FunctionBuilder::new(
+            &mut module.types,
+            &[ValType::I32, ValType::I32],
+            &[ValType::I32],
+        );
+
+        builder.name(String::from("godot_wasm.dictionary.erase"));
+
+        let RuntimeData { get_func, .. } = *runtime;
+
+        let o = module.locals.add(ValType::I32);
+        let k = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(o)
+            .call(get_func)
+            .local_get(k)
+            .call(get_func)
+            .call(import_func);
+
+        Ok(builder.finish(vec![o, k], &mut module.funcs))
+    })?;
+
+    replace_import(&mut *module, &mut *func_map, "dictionary.clear", |module| {
+        let ty = module.types.add(&[ValType::Externref], &[]);
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.clear", ty);
+        let mut builder = FunctionBuilder::new(&mut module.types, &[ValType::I32], &[]);
+
+        builder.name(String::from("godot_wasm.dictionary.clear"));
+
+        let RuntimeData { get_func, .. } = *runtime;
+
+        let o = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(o)
+            .call(get_func)
+            .call(import_func);
+
+        Ok(builder.finish(vec![o], &mut module.funcs))
+    })?;
+
+    replace_import(&mut *module, &mut *func_map, "dictionary.keys", |module| {
+        let ty = module
+            .types
+            .add(&[ValType::Externref], &[ValType::Externref]);
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "dictionary.keys", ty);
+        let mut builder = FunctionBuilder::new(&mut module.types, &[ValType::I32], &[ValType::I32]);
+
+        builder.name(String::from("godot_wasm.dictionary.keys"));
+
+        let RuntimeData {
+            get_func,
+            alloc_func,
+            ..
+        } = *runtime;
+
+        let o = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(o)
+            .call(get_func)
+            .call(import_func)
+            .call(alloc_func);
+
+        Ok(builder.finish(vec![o], &mut module.funcs))
+    })?;
+
+    replace_import(
+        &mut *module,
+        &mut *func_map,
+        "dictionary.values",
+        |module| {
+            let ty = module
+                .types
+                .add(&[ValType::Externref], &[ValType::Externref]);
+            let (import_func, _) =
+                module.add_import_func(EXTERNREF_MODULE, "dictionary.values", ty);
+            let mut builder =
+                FunctionBuilder::new(&mut module.types, &[ValType::I32], &[ValType::I32]);
+
+            builder.name(String::from("godot_wasm.dictionary.values"));
+
+            let RuntimeData {
+                get_func,
+                alloc_func,
+                ..
+            } = *runtime;
+
+            let o = module.locals.add(ValType::I32);
+
+            builder
+                .func_body()
+                .local_get(o)
+                .call(get_func)
+                .call(import_func)
+                .call(alloc_func);
+
+            Ok(builder.finish(vec![o], &mut module.funcs))
+        },
+    )?;
+
+    replace_import(
+        &mut *module,
+        &mut *func_map,
+        "dictionary.duplicate",
+        |module| {
+            let ty = module
+                .types
+                .add(&[ValType::Externref], &[ValType::Externref]);
+            let (import_func, _) =
+                module.add_import_func(EXTERNREF_MODULE, "dictionary.duplicate", ty);
+            let mut builder =
+                FunctionBuilder::new(&mut module.types, &[ValType::I32], &[ValType::I32]);
+
+            builder.name(String::from("godot_wasm.dictionary.duplicate"));
+
+            let RuntimeData {
+                get_func,
+                alloc_func,
+                ..
+            } = *runtime;
+
+            let o = module.locals.add(ValType::I32);
+
+            builder
+                .func_body()
+                .local_get(o)
+                .call(get_func)
+                .call(import_func)
+                .call(alloc_func);
+
+            Ok(builder.finish(vec![o], &mut module.funcs))
+        },
+    )?;
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 mod array;
+mod dictionary;
 mod other;
 mod pool_array;
 mod primitive;
@@ -8,8 +9,8 @@ mod typeis;
 
 use std::collections::HashMap;
 
-use anyhow::Error;
-use walrus::{FunctionId, ImportKind, Module};
+use anyhow::{bail, Error};
+use walrus::{FunctionId, ImportKind, Module, ValType};
 
 use crate::runtime::RuntimeData;
 use crate::util::map_substitute_funcs;
@@ -28,6 +29,7 @@ pub fn generate_imports(module: &mut Module, runtime: &RuntimeData) -> Result<()
 
     imports!((module, func_map, runtime) => [
         array,
+        dictionary,
         other,
         pool_array,
         primitive,
@@ -36,8 +38,6 @@ pub fn generate_imports(module: &mut Module, runtime: &RuntimeData) -> Result<()
         typeis,
     ]);
 
-    println!("{:?}", func_map);
-
     map_substitute_funcs(module, &func_map);
 
     Ok(())
@@ -64,3 +64,22 @@ where
 
     Ok(())
 }
+
+/// Gets host function, importing it if it's not imported yet.
+pub fn host_import(
+    module: &mut Module,
+    name: &str,
+    params: &[ValType],
+    results: &[ValType],
+) -> Result<FunctionId, Error> {
+    let ty = module.types.add(params, results);
+
+    if let Some(import_id) = module.imports.find(EXTERNREF_MODULE, name) {
+        return match module.imports.get(import_id).kind {
+            ImportKind::Function(f) if module.funcs.get(f).ty() == ty => Ok(f),
+            _ => bail!("Host import {} has mismatched type", name),
+        };
+    }
+
+    Ok(module.add_import_func(EXTERNREF_MODULE, name, ty).0)
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use anyhow::Error;
 use walrus::{FunctionBuilder, FunctionId, Module, ValType};
 
-use super::replace_import;
+use super::{replace_import, EXTERNREF_MODULE};
 use crate::runtime::RuntimeData;
 
 pub fn generate_imports(
@@ -47,5 +47,25 @@ pub fn generate_imports(
         Ok(builder.finish(vec![i], &mut module.funcs))
     })?;
 
+    replace_import(&mut *module, &mut *func_map, "error", |module| {
+        let ty = module.types.add(&[ValType::I32, ValType::I32], &[]);
+        let (import_func, _) = module.add_import_func(EXTERNREF_MODULE, "error", ty);
+        let mut builder =
+            FunctionBuilder::new(&mut module.types, &[ValType::I32, ValType::I32], &[]);
+
+        builder.name(String::from("godot_wasm.error"));
+
+        let p = module.locals.add(ValType::I32);
+        let n = module.locals.add(ValType::I32);
+
+        builder
+            .func_body()
+            .local_get(p)
+            .local_get(n)
+            .call(import_func);
+
+        Ok(builder.finish(vec![p, n], &mut module.funcs))
+    })?;
+
     Ok(())
 }
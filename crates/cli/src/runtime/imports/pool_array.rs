@@ -21,7 +21,7 @@ macro_rules! generate {
                     );
                     let (import_func, _) = module.add_import_func(
                         EXTERNREF_MODULE,
-                        $rname,
+                        $lname,
                         ty,
                     );
                     let mut builder = FunctionBuilder::new(
@@ -30,7 +30,7 @@ macro_rules! generate {
                         &[ValType::I32],
                     );
 
-                    builder.name(String::from(concat!("godot_wasm.", $rname)));
+                    builder.name(String::from(concat!("godot_wasm.", $lname)));
 
                     let RuntimeData {
                         get_func,
@@ -86,7 +86,7 @@ macro_rules! generate {
 
                 replace_import(&mut *module, &mut *func_map, $wname, |module| {
                     let ty = module.types.add(
-                        &[ValType::I32],
+                        &[ValType::I32, ValType::I32],
                         &[ValType::Externref],
                     );
                     let (import_func, _) = module.add_import_func(
@@ -96,7 +96,7 @@ macro_rules! generate {
                     );
                     let mut builder = FunctionBuilder::new(
                         &mut module.types,
-                        &[ValType::I32],
+                        &[ValType::I32, ValType::I32],
                         &[ValType::I32],
                     );
 
@@ -108,14 +108,16 @@ macro_rules! generate {
                     } = *runtime;
 
                     let p = module.locals.add(ValType::I32);
+                    let n = module.locals.add(ValType::I32);
 
                     builder
                         .func_body()
                         .local_get(p)
+                        .local_get(n)
                         .call(import_func)
                         .call(alloc_func);
 
-                    Ok(builder.finish(vec![p], &mut module.funcs))
+                    Ok(builder.finish(vec![p, n], &mut module.funcs))
                 })?;
             )*
 
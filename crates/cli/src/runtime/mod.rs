@@ -1,5 +1,7 @@
 mod imports;
 
+pub use imports::host_import;
+
 use anyhow::{bail, Error};
 use walrus::ir::{BinaryOp, ExtendedLoad, LoadKind, MemArg, StoreKind, UnaryOp, Value};
 use walrus::{
@@ -1,15 +1,20 @@
 use std::collections::HashMap;
 
-use anyhow::{bail, Error};
+use anyhow::{bail, Context, Error};
 use walrus::ir::{BinaryOp, UnaryOp, Value};
-use walrus::{ExportItem, FunctionBuilder, ImportKind, Module, ValType};
+use walrus::{ExportItem, FunctionBuilder, FunctionId, ImportKind, Module, ValType};
 
 use crate::decode::{
-    ArgType, ExportFunction, FunctionArgs, GodotWasmBindgenData, ImportFunction, SymbolType,
+    ArgType, ArrayInfo, ExportFunction, FunctionArgs, GodotWasmBindgenData, ImportFunction,
+    StartFunction, SymbolType,
 };
-use crate::runtime::RuntimeData;
+use crate::runtime::{host_import, RuntimeData};
 use crate::util::map_substitute_funcs;
 
+/// Wraps exported functions with thunks converting their arguments.
+///
+/// Function with optional parameters is also exported as `name.N` for every valid argument count N,
+/// which passes null for the omitted parameters.
 pub fn substitute_exports(
     module: &mut Module,
     custom_data: &GodotWasmBindgenData,
@@ -26,65 +31,72 @@ pub fn substitute_exports(
         .symbols
         .iter()
         .filter_map(|symbol| match &symbol.inner {
-            SymbolType::ExportFunction(e) => Some((&e.name as &str, &*e)),
+            SymbolType::ExportFunction(e) | SymbolType::ExportConst(e) => {
+                Some((&e.name as &str, e))
+            }
             _ => None,
         })
         .collect();
 
+    let classes: HashMap<_, _> = custom_data
+        .symbols
+        .iter()
+        .filter_map(|symbol| match &symbol.inner {
+            SymbolType::Class(c) => Some((&c.name as &str, c)),
+            _ => None,
+        })
+        .collect();
+
+    for e in exports.values() {
+        if let Some(class) = &e.class {
+            if !classes.contains_key(class as &str) {
+                bail!("Method {} belongs to undeclared class {}", e.name, class);
+            }
+        }
+    }
+
+    let arrays = array_funcs(module, custom_data)?;
+    let mut reduced = Vec::new();
+
     for e in module.exports.iter_mut() {
         let f = match &mut e.item {
             ExportItem::Function(f) => f,
             _ => continue,
         };
 
-        let ExportFunction {
-            args: FunctionArgs { params, results },
-            ..
-        } = match exports.get(&e.name as &str) {
+        let ExportFunction { args, .. } = match exports.get(&e.name as &str) {
             Some(&v) => v,
             None => continue,
         };
+        let FunctionArgs {
+            params, results, ..
+        } = args;
+
+        if let Some(n) = args
+            .named_params()
+            .chain(args.named_results())
+            .find_map(|(n, v)| matches!(v, ArgType::GodotValueRef).then_some(n))
+        {
+            bail!("Export {}: `{}` is a borrowed value", e.name, n);
+        }
+        if let Some((n, _)) = args
+            .optional_params()
+            .find(|(_, v)| !matches!(v, ArgType::GodotValue))
+        {
+            bail!(
+                "Export {}: optional parameter `{}` is not a value",
+                e.name,
+                n
+            );
+        }
 
         let func_params: Vec<_> = params.iter().copied().map(ValType::from).collect();
         let func_results: Vec<_> = results.iter().copied().map(ValType::from).collect();
 
         {
             let ty = module.types.get(module.funcs.get(*f).ty());
-            let (params, results) = (ty.params(), ty.results());
-
-            if func_params.len() != params.len() {
-                bail!(
-                    "Parameter length mismatch! ({} != {})",
-                    func_params.len(),
-                    params.len()
-                );
-            }
-            if func_results.len() != results.len() {
-                bail!(
-                    "Parameter length mismatch! ({} != {})",
-                    func_results.len(),
-                    results.len()
-                );
-            }
-
-            for (i, (&a, &b)) in func_params.iter().zip(params.iter()).enumerate() {
-                let a = match a {
-                    ValType::Externref => ValType::I32,
-                    v => v,
-                };
-                if a != b {
-                    bail!("Parameter type mismatch at {i} ({a} != {b})");
-                }
-            }
-            for (i, (&a, &b)) in func_results.iter().zip(results.iter()).enumerate() {
-                let a = match a {
-                    ValType::Externref => ValType::I32,
-                    v => v,
-                };
-                if a != b {
-                    bail!("Result type mismatch at {i} ({a} != {b})");
-                }
-            }
+            check_types(args, ty.params(), ty.results())
+                .with_context(|| format!("Export {}", e.name))?;
         }
 
         let mut builder = FunctionBuilder::new(&mut module.types, &func_params, &func_results);
@@ -100,23 +112,53 @@ pub fn substitute_exports(
             .map(|&ty| module.locals.add(ty))
             .collect();
         let temp = module.locals.add(ValType::I32);
+        let temp_len = module.locals.add(ValType::I32);
 
         let mut body = builder.func_body();
 
         for (i, &p) in var_params.iter().enumerate() {
-            body.local_get(p);
             match params[i] {
-                ArgType::U8 => body.const_(Value::I32(255)).binop(BinaryOp::I32And),
-                ArgType::I8 => body.unop(UnaryOp::I32Extend8S),
-                ArgType::U16 => body.const_(Value::I32(65535)).binop(BinaryOp::I32And),
-                ArgType::I16 => body.unop(UnaryOp::I32Extend16S),
+                ArgType::U8 => body
+                    .local_get(p)
+                    .const_(Value::I32(255))
+                    .binop(BinaryOp::I32And),
+                ArgType::I8 => body.local_get(p).unop(UnaryOp::I32Extend8S),
+                ArgType::U16 => body
+                    .local_get(p)
+                    .const_(Value::I32(65535))
+                    .binop(BinaryOp::I32And),
+                ArgType::I16 => body.local_get(p).unop(UnaryOp::I32Extend16S),
                 ArgType::U32
                 | ArgType::I32
                 | ArgType::U64
                 | ArgType::I64
                 | ArgType::F32
-                | ArgType::F64 => &mut body,
-                ArgType::GodotValue => body.call(alloc_func),
+                | ArgType::F64
+                | ArgType::Handle => body.local_get(p),
+                // Omitted optional parameter is passed as null by the `name.N` entry point,
+                // which becomes null handle. The shim then substitutes it with the default value.
+                ArgType::GodotValue => body.local_get(p).call(alloc_func),
+                ArgType::GodotValueRef => unreachable!("Borrowed value should get filtered"),
+                t => {
+                    let (info, funcs) = array_func(&arrays, t);
+
+                    // Copy array into newly allocated buffer, owned by the module
+                    body.local_get(p)
+                        .call(funcs.len)
+                        .local_set(temp_len)
+                        .local_get(temp_len)
+                        .const_(Value::I32(info.size as _))
+                        .binop(BinaryOp::I32Mul)
+                        .const_(Value::I32(info.align as _))
+                        .call(funcs.alloc)
+                        .local_set(temp)
+                        .local_get(p)
+                        .local_get(temp)
+                        .call(funcs.read)
+                        .drop()
+                        .local_get(temp)
+                        .local_get(temp_len)
+                }
             };
         }
 
@@ -130,7 +172,26 @@ pub fn substitute_exports(
                     .local_set(r)
                     .local_get(temp)
                     .call(free_func),
-                _ => body.local_set(r),
+                t => match t.array_info() {
+                    Some(_) => {
+                        let (info, funcs) = array_func(&arrays, t);
+
+                        // Copy array to host, then free the buffer
+                        body.local_set(temp_len)
+                            .local_set(temp)
+                            .local_get(temp)
+                            .local_get(temp_len)
+                            .call(funcs.write)
+                            .local_set(r)
+                            .local_get(temp)
+                            .local_get(temp_len)
+                            .const_(Value::I32(info.size as _))
+                            .binop(BinaryOp::I32Mul)
+                            .const_(Value::I32(info.align as _))
+                            .call(funcs.free)
+                    }
+                    None => body.local_set(r),
+                },
             };
         }
 
@@ -139,6 +200,40 @@ pub fn substitute_exports(
         }
 
         *f = builder.finish(var_params, &mut module.funcs);
+
+        for n in params.len() - args.optional..params.len() {
+            let mut builder =
+                FunctionBuilder::new(&mut module.types, &func_params[..n], &func_results);
+
+            let name = format!("{}.{}", e.name, n);
+            builder.name(name.clone());
+
+            let var_params: Vec<_> = func_params[..n]
+                .iter()
+                .map(|&ty| module.locals.add(ty))
+                .collect();
+
+            let mut body = builder.func_body();
+            for &p in &var_params {
+                body.local_get(p);
+            }
+            for _ in n..params.len() {
+                body.ref_null(ValType::Externref);
+            }
+            body.call(*f);
+
+            reduced.push((name, builder.finish(var_params, &mut module.funcs)));
+        }
+    }
+
+    for (name, f) in reduced {
+        if module.exports.iter().any(|e| e.name == name) {
+            bail!(
+                "Export {} conflicts with optional parameter entry point",
+                name
+            );
+        }
+        module.exports.add(&name, f);
     }
 
     Ok(())
@@ -157,12 +252,15 @@ pub fn substitute_imports(
     } = *runtime;
 
     let mut func_map = HashMap::new();
+    let arrays = array_funcs(module, custom_data)?;
 
     let imports: HashMap<_, _> = custom_data
         .symbols
         .iter()
         .filter_map(|symbol| match &symbol.inner {
-            SymbolType::ImportFunction(e) => Some(((&e.module as &str, &e.name as &str), &*e)),
+            SymbolType::ImportFunction(e) | SymbolType::ImportStatic(e) => {
+                Some(((&e.module as &str, &e.name as &str), e))
+            }
             _ => None,
         })
         .collect();
@@ -177,11 +275,22 @@ pub fn substitute_imports(
         let ImportFunction {
             module: module_,
             name,
-            args: FunctionArgs { params, results },
+            args,
+            ..
         } = match imports.get(&(&i.module as &str, &i.name as &str)) {
             Some(&v) => v,
             None => continue,
         };
+        let FunctionArgs {
+            params, results, ..
+        } = args;
+
+        if let Some(n) = args
+            .named_results()
+            .find_map(|(n, v)| matches!(v, ArgType::GodotValueRef).then_some(n))
+        {
+            bail!("Import {module_}.{name}: result `{n}` is a borrowed value");
+        }
 
         let (name, ty) = {
             let f = module.funcs.get(*f);
@@ -193,79 +302,78 @@ pub fn substitute_imports(
             )
         };
 
-        let mut func_params: Vec<_> = params.iter().copied().map(ValType::from).collect();
-        let mut func_results: Vec<_> = results.iter().copied().map(ValType::from).collect();
+        let host_params: Vec<_> = params.iter().copied().map(ValType::from).collect();
+        let host_results: Vec<_> = results.iter().copied().map(ValType::from).collect();
+        let func_params: Vec<_> = params
+            .iter()
+            .flat_map(|v| v.inner_types())
+            .copied()
+            .collect();
+        let func_results: Vec<_> = results
+            .iter()
+            .flat_map(|v| v.inner_types())
+            .copied()
+            .collect();
 
         let mut f_ = module
             .funcs
-            .add_import(module.types.add(&func_params, &func_results), id);
-
-        for i in func_params.iter_mut().chain(func_results.iter_mut()) {
-            if matches!(i, ValType::Externref) {
-                *i = ValType::I32;
-            }
-        }
+            .add_import(module.types.add(&host_params, &host_results), id);
 
         {
             let ty = module.types.get(ty);
-            let (params, results) = (ty.params(), ty.results());
-
-            if func_params.len() != params.len() {
-                bail!(
-                    "Parameter length mismatch! ({} != {})",
-                    func_params.len(),
-                    params.len()
-                );
-            }
-            if func_results.len() != results.len() {
-                bail!(
-                    "Parameter length mismatch! ({} != {})",
-                    func_results.len(),
-                    results.len()
-                );
-            }
-
-            for (i, (&a, &b)) in func_params.iter().zip(params.iter()).enumerate() {
-                if a != b {
-                    bail!("Parameter type mismatch at {i} ({a} != {b})");
-                }
-            }
-            for (i, (&a, &b)) in func_results.iter().zip(results.iter()).enumerate() {
-                if a != b {
-                    bail!("Result type mismatch at {i} ({a} != {b})");
-                }
-            }
+            check_types(args, ty.params(), ty.results())
+                .with_context(|| format!("Import {module_}.{name}"))?;
         }
 
         let mut builder = FunctionBuilder::new(&mut module.types, &func_params, &func_results);
 
         builder.name(name);
 
-        let var_params: Vec<_> = func_params
+        let var_params: Vec<Vec<_>> = params
             .iter()
-            .map(|&ty| module.locals.add(ty))
+            .map(|v| {
+                v.inner_types()
+                    .iter()
+                    .map(|&ty| module.locals.add(ty))
+                    .collect()
+            })
             .collect();
-        let var_results: Vec<_> = func_results
+        let var_results: Vec<Vec<_>> = results
             .iter()
-            .map(|&ty| module.locals.add(ty))
+            .map(|v| {
+                v.inner_types()
+                    .iter()
+                    .map(|&ty| module.locals.add(ty))
+                    .collect()
+            })
             .collect();
+        let temp = module.locals.add(ValType::Externref);
 
         let mut body = builder.func_body();
 
-        for (i, &p) in var_params.iter().enumerate() {
+        for (i, p) in var_params.iter().enumerate() {
             match params[i] {
                 ArgType::GodotValue => body
-                    .local_get(p)
+                    .local_get(p[0])
                     .call(get_func)
-                    .local_get(p)
+                    .local_get(p[0])
                     .call(free_func),
-                _ => body.local_get(p),
+                // Borrowed value is still owned by the caller.
+                ArgType::GodotValueRef => body.local_get(p[0]).call(get_func),
+                t => match t.array_info() {
+                    // Array is still owned by the caller.
+                    Some(_) => {
+                        let (_, funcs) = array_func(&arrays, t);
+                        body.local_get(p[0]).local_get(p[1]).call(funcs.write)
+                    }
+                    None => body.local_get(p[0]),
+                },
             };
         }
 
         body.call(f_);
 
-        for (i, &r) in var_results.iter().enumerate().rev() {
+        for (i, r) in var_results.iter().enumerate().rev() {
             match results[i] {
                 ArgType::U8 => body.const_(Value::I32(255)).binop(BinaryOp::I32And),
                 ArgType::I8 => body.unop(UnaryOp::I32Extend8S),
@@ -276,22 +384,410 @@ pub fn substitute_imports(
                 | ArgType::U64
                 | ArgType::I64
                 | ArgType::F32
-                | ArgType::F64 => &mut body,
+                | ArgType::F64
+                | ArgType::Handle => &mut body,
                 ArgType::GodotValue => body.call(alloc_func),
+                ArgType::GodotValueRef => unreachable!("Borrowed value should get filtered"),
+                t => {
+                    let (info, funcs) = array_func(&arrays, t);
+
+                    // Copy array into newly allocated buffer, owned by the module
+                    body.local_set(temp)
+                        .local_get(temp)
+                        .call(funcs.len)
+                        .local_set(r[1])
+                        .local_get(r[1])
+                        .const_(Value::I32(info.size as _))
+                        .binop(BinaryOp::I32Mul)
+                        .const_(Value::I32(info.align as _))
+                        .call(funcs.alloc)
+                        .local_set(r[0])
+                        .local_get(temp)
+                        .local_get(r[0])
+                        .call(funcs.read)
+                        .drop();
+                    continue;
+                }
             }
-            .local_set(r);
+            .local_set(r[0]);
         }
 
-        for &r in &var_results {
+        for &r in var_results.iter().flatten() {
             body.local_get(r);
         }
 
         (*f, f_) = (f_, *f);
         module.funcs.delete(f_);
-        func_map.insert(f_, builder.finish(var_params, &mut module.funcs));
+        func_map.insert(f_, builder.finish(var_params.concat(), &mut module.funcs));
     }
 
     map_substitute_funcs(module, &func_map);
 
     Ok(())
 }
+
+const INITIALIZE: &str = "_initialize";
+
+/// Exports of the async executor, linked in from the runtime crate.
+const EXECUTOR_EXPORTS: [&str; 2] = ["__godot_wasm_bindgen_poll", "__godot_wasm_bindgen_cancel"];
+
+/// Import called by the async executor to wake a task.
+const WAKE_IMPORT: (&str, &str) = ("host", "task.wake");
+
+/// Removes the async executor from a module without async functions.
+///
+/// The runtime crate always links the executor, which imports `host.task.wake`.
+/// Without async function no task can exist, so the executor exports are removed
+/// and the import is replaced by a trap. The module then instantiates on hosts without it.
+pub fn strip_executor(module: &mut Module, custom_data: &mut GodotWasmBindgenData) {
+    if custom_data
+        .symbols
+        .iter()
+        .any(|symbol| matches!(&symbol.inner, SymbolType::ExportFunction(e) if e.is_async))
+    {
+        return;
+    }
+
+    custom_data.symbols.retain(|symbol| match &symbol.inner {
+        SymbolType::ExportFunction(e) => !EXECUTOR_EXPORTS.contains(&&*e.name),
+        SymbolType::ImportFunction(i) => (&*i.module, &*i.name) != WAKE_IMPORT,
+        _ => true,
+    });
+
+    let exports: Vec<_> = module
+        .exports
+        .iter()
+        .filter(|e| EXECUTOR_EXPORTS.contains(&&*e.name))
+        .map(|e| e.id())
+        .collect();
+    for id in exports {
+        module.exports.delete(id);
+    }
+
+    let (id, f) = match module.imports.iter().find_map(|i| match i.kind {
+        ImportKind::Function(f) if (&*i.module, &*i.name) == WAKE_IMPORT => Some((i.id(), f)),
+        _ => None,
+    }) {
+        Some(v) => v,
+        None => return,
+    };
+
+    let ty = module.types.get(module.funcs.get(f).ty());
+    let (params, results) = (ty.params().to_vec(), ty.results().to_vec());
+    let mut builder = FunctionBuilder::new(&mut module.types, &params, &results);
+    builder.name(format!("{}.{}", WAKE_IMPORT.0, WAKE_IMPORT.1));
+    builder.func_body().unreachable();
+    let locals = params.iter().map(|&ty| module.locals.add(ty)).collect();
+    let stub = builder.finish(locals, &mut module.funcs);
+
+    module.imports.delete(id);
+    map_substitute_funcs(module, &HashMap::from([(f, stub)]));
+}
+
+/// Makes the start function run once after instantiation.
+///
+/// The start function is unexported, then called from `_initialize` if the module has it
+/// (so it runs after the module's own initialization), otherwise it's set as the start section.
+pub fn substitute_start(
+    module: &mut Module,
+    custom_data: &GodotWasmBindgenData,
+) -> Result<(), Error> {
+    let mut starts = custom_data
+        .symbols
+        .iter()
+        .filter_map(|symbol| match &symbol.inner {
+            SymbolType::Start(StartFunction { name }) => Some(name as &str),
+            _ => None,
+        });
+    let name = match starts.next() {
+        Some(v) => v,
+        None => return Ok(()),
+    };
+    if let Some(v) = starts.next() {
+        bail!("Multiple start functions ({}, {})", name, v);
+    }
+
+    let (id, f) = match module.exports.iter().find(|e| e.name == name) {
+        Some(e) => match e.item {
+            ExportItem::Function(f) => (e.id(), f),
+            _ => bail!("Start function {} is not a function", name),
+        },
+        None => bail!("Module does not export start function {}", name),
+    };
+    {
+        let ty = module.types.get(module.funcs.get(f).ty());
+        if !ty.params().is_empty() || !ty.results().is_empty() {
+            bail!(
+                "Start function {} must have no parameters and results",
+                name
+            );
+        }
+    }
+    module.exports.delete(id);
+
+    let initialize = module.exports.iter().find_map(|e| match e.item {
+        ExportItem::Function(f) if e.name == INITIALIZE => Some((e.id(), f)),
+        _ => None,
+    });
+
+    let f = match initialize.map(|(_, f)| f).or(module.start) {
+        // Chain after the existing initializer.
+        Some(prev) => {
+            let mut builder = FunctionBuilder::new(&mut module.types, &[], &[]);
+            builder.name(format!("{INITIALIZE}.{name}"));
+            builder.func_body().call(prev).call(f);
+            builder.finish(Vec::new(), &mut module.funcs)
+        }
+        None => f,
+    };
+
+    match initialize {
+        Some((id, _)) => module.exports.get_mut(id).item = ExportItem::Function(f),
+        None => module.start = Some(f),
+    }
+
+    Ok(())
+}
+
+/// Checks the types of the module function against the argument types.
+fn check_types(
+    args: &FunctionArgs,
+    func_params: &[ValType],
+    func_results: &[ValType],
+) -> Result<(), Error> {
+    fn check<'a>(
+        kind: &str,
+        args: impl Iterator<Item = (&'a str, ArgType)>,
+        mut types: &[ValType],
+    ) -> Result<(), Error> {
+        for (name, arg) in args {
+            let inner = arg.inner_types();
+            match types.get(..inner.len()) {
+                Some(v) if v == inner => types = &types[inner.len()..],
+                Some(v) => bail!("{kind} `{name}` type mismatch ({inner:?} != {v:?})"),
+                None => bail!("{kind} `{name}` is missing"),
+            }
+        }
+
+        if !types.is_empty() {
+            bail!("Too many {kind}s ({} extra)", types.len());
+        }
+        Ok(())
+    }
+
+    check("Parameter", args.named_params(), func_params)?;
+    check("Result", args.named_results(), func_results)
+}
+
+const LIBRARY_ALLOC: &str = "__godot_wasm_bindgen_alloc";
+const LIBRARY_FREE: &str = "__godot_wasm_bindgen_free";
+
+/// Functions used to pass an array.
+struct ArrayFuncs {
+    /// Allocates buffer in module (size, align) -> ptr.
+    alloc: FunctionId,
+    /// Frees buffer in module (ptr, size, align).
+    free: FunctionId,
+
+    len: FunctionId,
+    read: FunctionId,
+    write: FunctionId,
+}
+
+fn array_funcs(
+    module: &mut Module,
+    custom_data: &GodotWasmBindgenData,
+) -> Result<HashMap<ArgType, ArrayFuncs>, Error> {
+    let mut types: Vec<_> = custom_data
+        .symbols
+        .iter()
+        .filter_map(|symbol| match &symbol.inner {
+            SymbolType::ExportFunction(ExportFunction { args, .. })
+            | SymbolType::ExportConst(ExportFunction { args, .. })
+            | SymbolType::ImportFunction(ImportFunction { args, .. })
+            | SymbolType::ImportStatic(ImportFunction { args, .. }) => Some(args),
+            _ => None,
+        })
+        .flat_map(|args| args.params.iter().chain(args.results.iter()))
+        .copied()
+        .filter(|v| v.array_info().is_some())
+        .collect();
+    types.sort_by_key(|&v| v as u8);
+    types.dedup();
+
+    let mut ret = HashMap::new();
+    if types.is_empty() {
+        return Ok(ret);
+    }
+
+    let find_export = |name: &str| {
+        for e in module.exports.iter() {
+            if let (true, ExportItem::Function(f)) = (e.name == name, &e.item) {
+                return Ok(*f);
+            }
+        }
+        bail!("Module does not export {}", name)
+    };
+    let alloc = find_export(LIBRARY_ALLOC)?;
+    let free = find_export(LIBRARY_FREE)?;
+
+    for t in types {
+        let prefix = match t.array_info() {
+            Some(v) => v.prefix,
+            None => unreachable!("Non-array type should get filtered"),
+        };
+
+        let funcs = ArrayFuncs {
+            alloc,
+            free,
+            len: host_import(
+                module,
+                &format!("{prefix}.len"),
+                &[ValType::Externref],
+                &[ValType::I32],
+            )?,
+            read: host_import(
+                module,
+                &format!("{prefix}.read"),
+                &[ValType::Externref, ValType::I32],
+                &[ValType::I32],
+            )?,
+            write: host_import(
+                module,
+                &format!("{prefix}.write"),
+                &[ValType::I32, ValType::I32],
+                &[ValType::Externref],
+            )?,
+        };
+        ret.insert(t, funcs);
+    }
+
+    Ok(ret)
+}
+
+fn array_func(arrays: &HashMap<ArgType, ArrayFuncs>, t: ArgType) -> (ArrayInfo, &ArrayFuncs) {
+    match (t.array_info(), arrays.get(&t)) {
+        (Some(info), Some(funcs)) => (info, funcs),
+        _ => unreachable!("Array functions of {:?} should be generated", t),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus::passes::gc;
+    use walrus::{ElementKind, InitExpr, ModuleConfig};
+
+    use super::*;
+    use crate::decode::{Symbol, Version};
+
+    fn args(params: Vec<ArgType>) -> FunctionArgs {
+        FunctionArgs {
+            param_names: (0..params.len()).map(|i| format!("arg{i}")).collect(),
+            param_types: vec![None; params.len()],
+            params,
+            results: Vec::new(),
+            result_names: Vec::new(),
+            result_types: Vec::new(),
+            optional: 0,
+        }
+    }
+
+    fn export(name: &str, is_async: bool) -> Symbol {
+        Symbol {
+            version: Version::CURRENT,
+            inner: SymbolType::ExportFunction(ExportFunction {
+                class: None,
+                name: name.into(),
+                args: args(vec![ArgType::U32]),
+                doc: String::new(),
+                is_async,
+            }),
+        }
+    }
+
+    /// Module with the executor as linked from the runtime crate.
+    ///
+    /// The waker is also put in a table, like the vtable of a Rust waker.
+    fn executor_module(is_async: bool) -> (Module, GodotWasmBindgenData) {
+        let mut module = Module::default();
+        let ty = module.types.add(&[ValType::I32], &[]);
+        let (wake, _) = module.add_import_func(WAKE_IMPORT.0, WAKE_IMPORT.1, ty);
+
+        let add_func = |module: &mut Module, call: FunctionId| {
+            let mut builder = FunctionBuilder::new(&mut module.types, &[ValType::I32], &[]);
+            let p = module.locals.add(ValType::I32);
+            builder.func_body().local_get(p).call(call);
+            builder.finish(vec![p], &mut module.funcs)
+        };
+        let waker = add_func(&mut module, wake);
+        let table = module.tables.add_local(1, Some(1), ValType::Funcref);
+        module.elements.add(
+            ElementKind::Active {
+                table,
+                offset: InitExpr::Value(Value::I32(0)),
+            },
+            ValType::Funcref,
+            vec![Some(waker)],
+        );
+
+        let mut symbols = vec![Symbol {
+            version: Version::CURRENT,
+            inner: SymbolType::ImportFunction(ImportFunction {
+                module: WAKE_IMPORT.0.into(),
+                name: WAKE_IMPORT.1.into(),
+                args: args(vec![ArgType::U32]),
+                doc: String::new(),
+            }),
+        }];
+        let mut names = EXECUTOR_EXPORTS.to_vec();
+        if is_async {
+            names.push("spawn");
+        }
+        for name in names {
+            let f = add_func(&mut module, waker);
+            module.exports.add(name, f);
+            symbols.push(export(name, name == "spawn"));
+        }
+
+        (module, GodotWasmBindgenData { symbols })
+    }
+
+    /// Strips the executor, then reloads the emitted module.
+    fn strip(is_async: bool) -> (Module, GodotWasmBindgenData) {
+        let (mut module, mut custom_data) = executor_module(is_async);
+        strip_executor(&mut module, &mut custom_data);
+        gc::run(&mut module);
+        let module = ModuleConfig::new()
+            .parse(&module.emit_wasm())
+            .expect("stripped module is valid");
+        (module, custom_data)
+    }
+
+    fn has_wake_import(module: &Module) -> bool {
+        module
+            .imports
+            .iter()
+            .any(|i| (&*i.module, &*i.name) == WAKE_IMPORT)
+    }
+
+    #[test]
+    fn strip_executor_without_async() {
+        let (module, custom_data) = strip(false);
+
+        assert!(!has_wake_import(&module));
+        assert!(module.imports.iter().next().is_none());
+        assert!(module.exports.iter().next().is_none());
+        assert!(custom_data.symbols.is_empty());
+    }
+
+    #[test]
+    fn keep_executor_with_async() {
+        let (module, custom_data) = strip(true);
+
+        assert!(has_wake_import(&module));
+        for name in EXECUTOR_EXPORTS {
+            assert!(module.exports.iter().any(|e| e.name == name));
+        }
+        assert_eq!(custom_data.symbols.len(), 4);
+    }
+}
@@ -17,7 +17,7 @@ pub fn tag_length(bytes: &mut Vec<u8>) {
 pub fn prepend(bytes: &mut Vec<u8>, data: &[u8]) {
     let l = bytes.len();
     let ld = data.len();
-    bytes.extend(iter::repeat(0).take(ld));
+    bytes.extend(iter::repeat_n(0, ld));
     bytes.copy_within(0..l, ld);
     bytes[0..ld].copy_from_slice(data);
 }
@@ -26,7 +26,7 @@ pub fn read_tagged<F, R>(reader: &mut dyn Read, f: F) -> Result<R, Error>
 where
     for<'a> F: FnOnce(&'a mut dyn Read) -> Result<R, Error>,
 {
-    let len = leb128::read::unsigned(&mut *reader)?.try_into()?;
+    let len = leb128::read::unsigned(&mut *reader)?;
     f(&mut reader.take(len))
 }
 
@@ -41,7 +41,7 @@ pub fn map_substitute_funcs(module: &mut Module, func_map: &HashMap<FunctionId,
         }
     }
 
-    let mut substitutor = Substitutor(&func_map);
+    let mut substitutor = Substitutor(func_map);
 
     for (_, f) in module.funcs.iter_local_mut() {
         dfs_pre_order_mut(&mut substitutor, f, f.entry_block());
@@ -53,7 +53,7 @@ pub fn map_substitute_funcs(module: &mut Module, func_map: &HashMap<FunctionId,
         }
 
         for i in e.members.iter_mut().filter_map(|i| i.as_mut()) {
-            if let Some(&id) = func_map.get(&i) {
+            if let Some(&id) = func_map.get(i) {
                 *i = id;
             }
         }
@@ -70,7 +70,7 @@ pub fn map_substitute_funcs(module: &mut Module, func_map: &HashMap<FunctionId,
 
     for id in ids {
         if let GlobalKind::Local(InitExpr::RefFunc(i)) = &mut module.globals.get_mut(id).kind {
-            if let Some(&id) = func_map.get(&i) {
+            if let Some(&id) = func_map.get(i) {
                 *i = id;
             }
         }
@@ -0,0 +1,441 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::Result as ParseResult;
+use syn::spanned::Spanned;
+use syn::{
+    Expr, FnArg, ImplItem, ImplItemMethod, ItemImpl, ItemStruct, ReturnType, Signature, Type,
+    Visibility,
+};
+
+use crate::parser::{
+    check_native_output, check_native_result, doc_string, native_data, native_output,
+    native_output_data, param_arg, param_check, param_cvt, param_data, param_name, raw_symbol_data,
+    result_err_cvt, result_names, symbol_data, take_param_default, take_param_mode, unwrap_result,
+    variant_data, variant_null, variant_output, variant_result_cvt, write_name, BindgenMetadata,
+    SymbolInfo,
+};
+use crate::util::join_errors;
+
+pub struct BindgenImpl {
+    metadata: BindgenMetadata,
+    item: ItemImpl,
+
+    class_name: String,
+    methods: Vec<BindgenMethod>,
+}
+
+struct BindgenMethod {
+    name: String,
+    sig: Signature,
+    output: ReturnType,
+    doc: String,
+
+    receiver: Option<bool>,
+    native_params: Vec<bool>,
+    defaults: Vec<Option<Expr>>,
+    returns_self: bool,
+    is_result: bool,
+}
+
+impl BindgenImpl {
+    pub fn new(metadata: BindgenMetadata, mut item: ItemImpl) -> ParseResult<Self> {
+        if let Some((_, path, _)) = &item.trait_ {
+            bail_syn!(path.span(), "Trait implementation cannot be exported");
+        }
+        if !item.generics.params.is_empty() {
+            bail_syn!(item.generics.span(), "Generic type cannot be exported");
+        }
+        if let Some(v) = &metadata.name {
+            bail_syn!(v.span(), "Exported class cannot be renamed");
+        }
+        if let Some(v) = metadata.returns.iter().flatten().next() {
+            bail_syn!(v.span(), "Result names cannot be set on impl block");
+        }
+        if metadata.no_constructor {
+            bail_syn!(item.span(), "Only exported struct can disable constructor");
+        }
+        if metadata.start {
+            bail_syn!(item.span(), "Start function must be an exported function");
+        }
+        if let Some((t, _)) = metadata.instantiate.iter().flatten().next() {
+            bail_syn!(t.span(), "Only generic function can be instantiated");
+        }
+
+        let class_name = match &*item.self_ty {
+            Type::Path(p) if p.qself.is_none() => match p.path.segments.last() {
+                Some(v) if v.arguments.is_empty() => format!("{}{}", metadata.prefix, v.ident),
+                _ => bail_syn!(p.span(), "Generic type cannot be exported"),
+            },
+            t => bail_syn!(t.span(), "Unsupported type (must be a path)"),
+        };
+
+        let mut errs = None;
+        let mut methods = Vec::new();
+        for i in item.items.iter_mut() {
+            let f = match i {
+                ImplItem::Method(f) if matches!(f.vis, Visibility::Public(_)) => f,
+                _ => continue,
+            };
+
+            if let Some(v) = join_errors(
+                &mut errs,
+                BindgenMethod::new(&metadata, &class_name, &item.self_ty, f),
+            ) {
+                methods.push(v);
+            }
+        }
+
+        if let Some(e) = errs {
+            return Err(e);
+        }
+
+        Ok(Self {
+            metadata,
+            item,
+
+            class_name,
+            methods,
+        })
+    }
+}
+
+impl BindgenMethod {
+    fn new(
+        metadata: &BindgenMetadata,
+        class_name: &str,
+        self_ty: &Type,
+        item: &mut ImplItemMethod,
+    ) -> ParseResult<Self> {
+        let sig = &mut item.sig;
+        if !sig.generics.params.is_empty() {
+            bail_syn!(sig.generics.span(), "Generic method cannot be exported");
+        }
+        if let Some(v) = &sig.asyncness {
+            bail_syn!(v.span(), "Async method cannot be exported");
+        }
+
+        let mut receiver = None;
+        let mut native_params = Vec::new();
+        let mut defaults: Vec<Option<Expr>> = Vec::new();
+        for (i, v) in sig.inputs.iter_mut().enumerate() {
+            match v {
+                FnArg::Receiver(r) if i == 0 => match &r.reference {
+                    Some(_) => receiver = Some(r.mutability.is_some()),
+                    None => bail_syn!(r.span(), "Consuming method cannot be exported"),
+                },
+                FnArg::Receiver(r) => bail_syn!(r.span(), "Invalid receiver"),
+                FnArg::Typed(t) => {
+                    let n = take_param_mode(&mut t.attrs, metadata.use_native_types)?;
+                    let has_optional = defaults.iter().any(Option::is_some);
+                    defaults.push(take_param_default(t, n, has_optional)?);
+                    native_params.push(n);
+                }
+            }
+        }
+
+        let (output, is_result) = match unwrap_result(&sig.output) {
+            Some(v) => (v, true),
+            None => (sig.output.clone(), false),
+        };
+        if metadata.use_native_types {
+            check_native_output(&output, false)?;
+        }
+
+        let returns_self = match &output {
+            ReturnType::Type(_, t) => match &**t {
+                Type::Path(p) => {
+                    p.path.is_ident("Self")
+                        || p.to_token_stream().to_string() == self_ty.to_token_stream().to_string()
+                }
+                _ => false,
+            },
+            ReturnType::Default => false,
+        };
+        // Failed constructor returns null handle regardless of mode.
+        if metadata.use_native_types && is_result && !returns_self {
+            check_native_result(metadata, &sig.output, &output)?;
+        }
+
+        Ok(Self {
+            name: format!("{}_{}", class_name, sig.ident),
+            sig: sig.clone(),
+            output,
+            doc: doc_string(&item.attrs),
+
+            receiver,
+            native_params,
+            defaults,
+            returns_self,
+            is_result,
+        })
+    }
+
+    fn to_tokens(
+        &self,
+        metadata: &BindgenMetadata,
+        class_name: &str,
+        self_ty: &Type,
+    ) -> TokenStream {
+        let name = &self.name;
+        let func_ident = &self.sig.ident;
+        let inputs: Vec<_> = self
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|v| match v {
+                FnArg::Typed(v) => Some(v),
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+
+        let this_arg = self.receiver.map(|_| quote!(this: Handle,));
+        let this_get = self
+            .receiver
+            .map(|_| quote!(let this = this.get::<#self_ty>();));
+        let this_cvt = match self.receiver {
+            Some(false) => quote!(&this.borrow(),),
+            Some(true) => quote!(&mut this.borrow_mut(),),
+            None => quote!(),
+        };
+
+        let inputs = || inputs.iter().zip(self.native_params.iter()).enumerate();
+        let param_args = inputs().map(|(i, (v, &n))| param_arg(&v.ty, i, n));
+        let param_cvt = inputs()
+            .zip(self.defaults.iter())
+            .map(|((i, (v, &n)), d)| param_cvt(&v.ty, i, n, d.as_ref()));
+
+        let output = &self.output;
+        let (result_args, ret_cvt, fallback) = if self.returns_self {
+            (
+                quote!(-> Handle),
+                quote!(Handle::new(ret)),
+                quote!(return Handle::null()),
+            )
+        } else if metadata.use_native_types {
+            let (result_args, ret_cvt) = native_output(output);
+            (result_args, ret_cvt, quote!(Default::default()))
+        } else {
+            let null = variant_null(output, true);
+            (
+                variant_output(output, true),
+                variant_result_cvt(output, true),
+                quote!(return #null),
+            )
+        };
+        let err_cvt = if self.is_result {
+            result_err_cvt(metadata.on_error.as_ref(), fallback)
+        } else {
+            quote!()
+        };
+
+        let handle_data = native_data(&parse_quote!(Handle));
+
+        let mut params_data: Vec<_> = self.receiver.iter().map(|_| handle_data.clone()).collect();
+        let result_data = if self.returns_self {
+            vec![handle_data]
+        } else if metadata.use_native_types {
+            native_output_data(output)
+        } else {
+            variant_data(output, false, true)
+        };
+        params_data.extend(inputs().map(|(i, (v, &n))| param_data(&v.ty, i, n)));
+        let param_checks = inputs().map(|(i, (v, &n))| param_check(&v.pat, i, n));
+
+        let mut bytes: Vec<u8> = Vec::new();
+        leb128::write::unsigned(&mut bytes, 65).unwrap();
+        write_name(&mut bytes, class_name);
+        write_name(&mut bytes, name);
+
+        let mut param_names: Vec<_> = self.receiver.iter().map(|_| "self".into()).collect();
+        param_names.extend(inputs().map(|(i, (v, _))| param_name(&v.pat, i)));
+        let info = SymbolInfo {
+            param_names,
+            result_names: result_names(None, result_data.len()),
+            optional: self.defaults.iter().filter(|v| v.is_some()).count(),
+            doc: self.doc.clone(),
+        };
+
+        let data = symbol_data(bytes, params_data, result_data, &info);
+
+        quote!(
+            const _: () = {
+                use godot_wasm_bindgen::__hidden::{
+                    from_godot, into_raw_string, into_raw_vec, raw_string, raw_vec, report_error,
+                    FromGodot, GodotValue, Handle, IntoGodot, NativeType, TypeInfo,
+                };
+
+                #(#param_checks)*
+
+                #[export_name = #name]
+                #[doc(hidden)]
+                pub extern "C" fn export_function(#this_arg #(#param_args),*) #result_args {
+                    #this_get
+                    let ret = <#self_ty>::#func_ident ( #this_cvt #(#param_cvt),* );
+                    #err_cvt
+                    #ret_cvt
+                }
+
+                #data
+            };
+        )
+    }
+}
+
+impl ToTokens for BindgenImpl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.item.to_tokens(tokens);
+
+        for m in self.methods.iter() {
+            m.to_tokens(&self.metadata, &self.class_name, &self.item.self_ty)
+                .to_tokens(tokens);
+        }
+    }
+}
+
+pub struct BindgenStruct {
+    metadata: BindgenMetadata,
+    item: ItemStruct,
+}
+
+impl BindgenStruct {
+    pub fn new(metadata: BindgenMetadata, item: ItemStruct) -> ParseResult<Self> {
+        if !matches!(&item.vis, Visibility::Public(_)) {
+            bail_syn!(item.vis.span(), "Visibility must be public");
+        }
+        if !item.generics.params.is_empty() {
+            bail_syn!(item.generics.span(), "Generic type cannot be exported");
+        }
+        if let Some(v) = &metadata.name {
+            bail_syn!(v.span(), "Exported class cannot be renamed");
+        }
+        if metadata.use_native_types {
+            bail_syn!(item.span(), "Struct cannot use native types");
+        }
+        if let Some(v) = &metadata.on_error {
+            bail_syn!(v.span(), "Error value cannot be set on struct");
+        }
+        if let Some(v) = &metadata.returns {
+            let span = v.first().map_or_else(|| item.span(), |v| v.span());
+            bail_syn!(span, "Result names cannot be set on struct");
+        }
+        if metadata.start {
+            bail_syn!(item.span(), "Start function must be an exported function");
+        }
+        if let Some((t, _)) = metadata.instantiate.iter().flatten().next() {
+            bail_syn!(t.span(), "Only generic function can be instantiated");
+        }
+
+        Ok(Self { metadata, item })
+    }
+}
+
+impl ToTokens for BindgenStruct {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.item.to_tokens(tokens);
+
+        let ident = &self.item.ident;
+        let class_name = format!("{}{}", self.metadata.prefix, ident);
+        let handle_data = native_data(&parse_quote!(Handle));
+
+        quote!(
+            impl godot_wasm_bindgen::class::GodotClass for #ident {
+                fn registry() -> &'static ::std::thread::LocalKey<
+                    ::std::cell::RefCell<godot_wasm_bindgen::class::Registry<::std::cell::RefCell<Self>>>,
+                > {
+                    ::std::thread_local! {
+                        static REGISTRY: ::std::cell::RefCell<
+                            godot_wasm_bindgen::class::Registry<::std::cell::RefCell<#ident>>,
+                        > = const { ::std::cell::RefCell::new(godot_wasm_bindgen::class::Registry::new()) };
+                    }
+                    &REGISTRY
+                }
+            }
+        )
+        .to_tokens(tokens);
+
+        let constructor = if self.metadata.no_constructor {
+            String::new()
+        } else {
+            let name = format!("{}_new", class_name);
+
+            let mut bytes: Vec<u8> = Vec::new();
+            leb128::write::unsigned(&mut bytes, 65).unwrap();
+            write_name(&mut bytes, &class_name);
+            write_name(&mut bytes, &name);
+
+            let info = SymbolInfo {
+                param_names: Vec::new(),
+                result_names: result_names(None, 1),
+                optional: 0,
+                doc: String::new(),
+            };
+
+            let data = symbol_data(bytes, Vec::new(), vec![handle_data.clone()], &info);
+
+            quote!(
+                const _: () = {
+                    use godot_wasm_bindgen::__hidden::{NativeType, Handle};
+
+                    #[export_name = #name]
+                    #[doc(hidden)]
+                    pub extern "C" fn export_function() -> Handle {
+                        Handle::new(<#ident as Default>::default())
+                    }
+
+                    #data
+                };
+            )
+            .to_tokens(tokens);
+
+            name
+        };
+
+        let destructor = format!("{}_drop", class_name);
+        {
+            let mut bytes: Vec<u8> = Vec::new();
+            leb128::write::unsigned(&mut bytes, 65).unwrap();
+            write_name(&mut bytes, &class_name);
+            write_name(&mut bytes, &destructor);
+
+            let info = SymbolInfo {
+                param_names: vec!["self".into()],
+                result_names: Vec::new(),
+                optional: 0,
+                doc: String::new(),
+            };
+
+            let data = symbol_data(bytes, vec![handle_data], Vec::new(), &info);
+
+            quote!(
+                const _: () = {
+                    use godot_wasm_bindgen::__hidden::{NativeType, Handle};
+
+                    #[export_name = #destructor]
+                    #[doc(hidden)]
+                    pub extern "C" fn export_function(this: Handle) {
+                        this.delete::<#ident>()
+                    }
+
+                    #data
+                };
+            )
+            .to_tokens(tokens);
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        leb128::write::unsigned(&mut bytes, 66).unwrap();
+        write_name(&mut bytes, &class_name);
+        write_name(&mut bytes, &constructor);
+        write_name(&mut bytes, &destructor);
+        write_name(&mut bytes, &doc_string(&self.item.attrs));
+
+        let data = raw_symbol_data(bytes);
+
+        quote!(
+            const _: () = {
+                #data
+            };
+        )
+        .to_tokens(tokens);
+    }
+}
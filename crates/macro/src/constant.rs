@@ -0,0 +1,111 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::Result as ParseResult;
+use syn::spanned::Spanned;
+use syn::{ItemConst, ReturnType, Type, Visibility};
+
+use crate::parser::{
+    check_native_output, doc_string, native_data, native_output, typed_symbol_data, value_data,
+    write_name, BindgenMetadata,
+};
+
+/// Constant exported as a getter without parameters.
+pub struct BindgenConst {
+    metadata: BindgenMetadata,
+    item: ItemConst,
+
+    name: String,
+}
+
+impl BindgenConst {
+    pub fn new(metadata: BindgenMetadata, item: ItemConst) -> ParseResult<Self> {
+        if !matches!(&item.vis, Visibility::Public(_)) {
+            bail_syn!(item.vis.span(), "Visibility must be public");
+        }
+        if let Some(v) = &metadata.on_error {
+            bail_syn!(v.span(), "Constant cannot fail");
+        }
+        if metadata.no_constructor {
+            bail_syn!(item.span(), "Only exported struct can disable constructor");
+        }
+        if let Some(v) = &metadata.returns {
+            let span = v.first().map_or_else(|| item.span(), |v| v.span());
+            bail_syn!(span, "Result names cannot be set on constant");
+        }
+        if metadata.start {
+            bail_syn!(item.span(), "Start function must be an exported function");
+        }
+        if let Some((t, _)) = metadata.instantiate.iter().flatten().next() {
+            bail_syn!(t.span(), "Only generic function can be instantiated");
+        }
+        if metadata.use_native_types {
+            if let Type::Tuple(t) = &*item.ty {
+                bail_syn!(t.span(), "Tuple constant cannot be exported natively");
+            }
+            check_native_output(&Self::output(&item), false)?;
+        }
+
+        let name = match &metadata.name {
+            Some(v) => format!("{}{}", metadata.prefix, v.value()),
+            None => format!("{}{}", metadata.prefix, item.ident),
+        };
+
+        Ok(Self {
+            metadata,
+            item,
+            name,
+        })
+    }
+
+    fn output(item: &ItemConst) -> ReturnType {
+        ReturnType::Type(Token![->](item.colon_token.span), item.ty.clone())
+    }
+}
+
+impl ToTokens for BindgenConst {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.item.to_tokens(tokens);
+
+        let name = &self.name;
+        let ident = &self.item.ident;
+
+        // Unlike functions, tuple is returned as a single array.
+        let (output, ret_cvt, ty_data) = if self.metadata.use_native_types {
+            let (output, ret_cvt) = native_output(&Self::output(&self.item));
+            (output, ret_cvt, native_data(&self.item.ty))
+        } else {
+            (
+                quote!(-> GodotValue),
+                quote!(IntoGodot::into_godot(ret)),
+                value_data(&self.item.ty, true),
+            )
+        };
+
+        let mut bytes: Vec<u8> = Vec::new();
+        leb128::write::unsigned(&mut bytes, 68).unwrap();
+        write_name(&mut bytes, name);
+
+        let mut doc = Vec::new();
+        write_name(&mut doc, &doc_string(&self.item.attrs));
+
+        let data = typed_symbol_data(bytes, vec![ty_data], doc);
+
+        quote!(
+            const _: () = {
+                use godot_wasm_bindgen::__hidden::{
+                    into_raw_string, into_raw_vec, NativeType, GodotValue, IntoGodot,
+                };
+
+                #[export_name = #name]
+                #[doc(hidden)]
+                pub extern "C" fn export_function() #output {
+                    let ret = #ident;
+                    #ret_cvt
+                }
+
+                #data
+            };
+        )
+        .to_tokens(tokens);
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::parse::{Error as ParseError, Result as ParseResult};
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DataEnum, DeriveInput, Fields, Index, Lit, LitStr, Meta, NestedMeta};
+
+use crate::util::join_errors;
+
+/// Options of `#[godot(...)]` attribute.
+#[derive(Default)]
+struct GodotAttr {
+    rename: Option<LitStr>,
+    default: Option<Span>,
+    /// Either `"int"` (enum discriminant) or `"string"` (variant name).
+    repr: Option<LitStr>,
+}
+
+impl GodotAttr {
+    fn new(attrs: &[Attribute]) -> ParseResult<Self> {
+        let mut ret = Self::default();
+
+        for a in attrs.iter().filter(|a| a.path.is_ident("godot")) {
+            let list = match a.parse_meta()? {
+                Meta::List(v) => v.nested,
+                m => bail_syn!(m.span(), "Expected #[godot(...)]"),
+            };
+
+            for m in list {
+                match m {
+                    NestedMeta::Meta(Meta::NameValue(v)) if v.path.is_ident("rename") => {
+                        if ret.rename.is_some() {
+                            bail_syn!(v.span(), "Duplicate rename");
+                        }
+                        match v.lit {
+                            Lit::Str(s) => ret.rename = Some(s),
+                            l => bail_syn!(l.span(), "Expected string"),
+                        }
+                    }
+                    NestedMeta::Meta(Meta::Path(v)) if v.is_ident("default") => {
+                        if ret.default.is_some() {
+                            bail_syn!(v.span(), "Duplicate default");
+                        }
+                        ret.default = Some(v.span());
+                    }
+                    NestedMeta::Meta(Meta::NameValue(v)) if v.path.is_ident("repr") => {
+                        if ret.repr.is_some() {
+                            bail_syn!(v.span(), "Duplicate repr");
+                        }
+                        match v.lit {
+                            Lit::Str(s) if s.value() == "int" || s.value() == "string" => {
+                                ret.repr = Some(s)
+                            }
+                            l => bail_syn!(l.span(), "Expected \"int\" or \"string\""),
+                        }
+                    }
+                    m => bail_syn!(m.span(), "Unknown option"),
+                }
+            }
+        }
+
+        Ok(ret)
+    }
+
+    fn is_string_repr(&self) -> bool {
+        matches!(&self.repr, Some(v) if v.value() == "string")
+    }
+
+    fn check_item(&self, is_enum: bool) -> ParseResult<()> {
+        if let Some(v) = &self.rename {
+            bail_syn!(v.span(), "Item cannot be renamed");
+        }
+        if let Some(v) = self.default {
+            bail_syn!(v, "Default is only supported on field");
+        }
+        match &self.repr {
+            Some(v) if !is_enum => bail_syn!(v.span(), "Repr is only supported on enum"),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_field(&self) -> ParseResult<()> {
+        match &self.repr {
+            Some(v) => bail_syn!(v.span(), "Repr is only supported on enum"),
+            None => Ok(()),
+        }
+    }
+
+    fn check_unnamed(&self) -> ParseResult<()> {
+        if let Some(v) = &self.rename {
+            bail_syn!(v.span(), "Unnamed field cannot be renamed");
+        }
+        if let Some(v) = self.default {
+            bail_syn!(v, "Default is only supported on named field");
+        }
+        self.check_field()
+    }
+
+    fn check_variant(&self, string_repr: bool) -> ParseResult<()> {
+        if let Some(v) = self.default {
+            bail_syn!(v, "Default is only supported on field");
+        }
+        if let Some(v) = &self.repr {
+            bail_syn!(v.span(), "Repr is only supported on enum");
+        }
+        match &self.rename {
+            Some(v) if !string_repr => {
+                bail_syn!(v.span(), "Rename requires #[godot(repr = \"string\")]")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct GodotConvert {
+    item: DeriveInput,
+}
+
+impl GodotConvert {
+    pub fn new(item: DeriveInput) -> ParseResult<Self> {
+        let attr = GodotAttr::new(&item.attrs)?;
+        attr.check_item(matches!(item.data, Data::Enum(_)))?;
+
+        let mut errs = None;
+        match &item.data {
+            Data::Struct(s) => match &s.fields {
+                Fields::Named(f) => {
+                    for f in f.named.iter() {
+                        join_errors(&mut errs, GodotAttr::new(&f.attrs)?.check_field());
+                    }
+                }
+                Fields::Unnamed(f) => {
+                    for f in f.unnamed.iter() {
+                        join_errors(&mut errs, GodotAttr::new(&f.attrs)?.check_unnamed());
+                    }
+                }
+                Fields::Unit => bail_syn!(item.ident.span(), "Unit struct is unsupported"),
+            },
+            Data::Enum(e) => {
+                if !item.generics.params.is_empty() {
+                    bail_syn!(item.generics.span(), "Generic enum is unsupported");
+                }
+
+                for v in e.variants.iter() {
+                    if !matches!(v.fields, Fields::Unit) {
+                        join_errors::<()>(
+                            &mut errs,
+                            Err(ParseError::new(
+                                v.fields.span(),
+                                "Variant must be fieldless",
+                            )),
+                        );
+                        continue;
+                    }
+
+                    join_errors(
+                        &mut errs,
+                        GodotAttr::new(&v.attrs)?.check_variant(attr.is_string_repr()),
+                    );
+                }
+
+                if attr.is_string_repr() {
+                    // Otherwise the later variant can never be converted from Godot.
+                    let mut names = HashSet::new();
+                    for v in e.variants.iter() {
+                        let (name, span) = match GodotAttr::new(&v.attrs)?.rename {
+                            Some(n) => (n.value(), n.span()),
+                            None => (v.ident.to_string(), v.ident.span()),
+                        };
+                        if !names.insert(name.clone()) {
+                            join_errors::<()>(
+                                &mut errs,
+                                Err(ParseError::new(
+                                    span,
+                                    format!("Duplicate variant name {:?}", name),
+                                )),
+                            );
+                        }
+                    }
+                }
+            }
+            Data::Union(u) => bail_syn!(u.union_token.span(), "Union is unsupported"),
+        }
+
+        if let Some(e) = errs {
+            return Err(e);
+        }
+
+        Ok(Self { item })
+    }
+}
+
+impl ToTokens for GodotConvert {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let DeriveInput {
+            ident,
+            generics,
+            data,
+            ..
+        } = &self.item;
+
+        let mut from_generics = generics.clone();
+        let mut into_generics = generics.clone();
+        if let (false, Data::Struct(s)) = (generics.params.is_empty(), data) {
+            let from_clause = from_generics.make_where_clause();
+            let into_clause = into_generics.make_where_clause();
+            for f in s.fields.iter() {
+                let ty = &f.ty;
+                from_clause.predicates.push(parse_quote!(#ty: FromGodot));
+                from_clause
+                    .predicates
+                    .push(parse_quote!(<#ty as FromGodot>::Error: ::std::error::Error + 'static));
+                into_clause.predicates.push(parse_quote!(#ty: IntoGodot));
+            }
+        }
+        let (impl_generics, ty_generics, _) = generics.split_for_impl();
+        let (_, _, from_where) = from_generics.split_for_impl();
+        let (_, _, into_where) = into_generics.split_for_impl();
+
+        let mut error_ty = quote!(ConvertError);
+        let (from_body, into_body) = match data {
+            Data::Struct(s) => match &s.fields {
+                Fields::Unnamed(f) if f.unnamed.len() == 1 => {
+                    let ty = &f.unnamed[0].ty;
+                    error_ty = quote!(<#ty as FromGodot>::Error);
+                    newtype_struct()
+                }
+                Fields::Unnamed(_) => tuple_struct(&s.fields),
+                _ => named_struct(&s.fields),
+            },
+            Data::Enum(e) if GodotAttr::new(&self.item.attrs).unwrap().is_string_repr() => {
+                string_enum(e)
+            }
+            Data::Enum(e) => int_enum(e),
+            Data::Union(_) => unreachable!("Union is unsupported"),
+        };
+        let from_type = type_info(&self.item, quote!(FromGodot));
+        let into_type = type_info(&self.item, quote!(IntoGodot));
+
+        quote!(
+            const _: () = {
+                use godot_wasm_bindgen::__hidden::{
+                    array_check_len, array_get, dict_get, dict_set, ConvertError, FromGodot,
+                    GodotArray, GodotDictionary, GodotValue, IntoGodot, TypeInfo,
+                };
+
+                impl #impl_generics FromGodot for #ident #ty_generics #from_where {
+                    type Error = #error_ty;
+
+                    const TYPE: TypeInfo = #from_type;
+
+                    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+                        #from_body
+                    }
+                }
+
+                impl #impl_generics IntoGodot for #ident #ty_generics #into_where {
+                    const TYPE: TypeInfo = #into_type;
+
+                    fn into_godot(self) -> GodotValue {
+                        #into_body
+                    }
+                }
+            };
+        )
+        .to_tokens(tokens);
+    }
+}
+
+/// Type of the converted value, with field types taken from `tr` (either conversion trait).
+fn type_info(item: &DeriveInput, tr: TokenStream) -> TokenStream {
+    let s = match &item.data {
+        Data::Struct(s) => s,
+        Data::Enum(_) if GodotAttr::new(&item.attrs).unwrap().is_string_repr() => {
+            return quote!(<String as #tr>::TYPE)
+        }
+        Data::Enum(_) => return quote!(<i64 as #tr>::TYPE),
+        Data::Union(_) => unreachable!("Union is unsupported"),
+    };
+
+    match &s.fields {
+        Fields::Unnamed(f) if f.unnamed.len() == 1 => {
+            let ty = &f.unnamed[0].ty;
+            quote!(<#ty as #tr>::TYPE)
+        }
+        Fields::Unnamed(f) => {
+            let ty = f.unnamed.iter().map(|f| &f.ty);
+            quote!(TypeInfo::Tuple(&[ #(<#ty as #tr>::TYPE),* ]))
+        }
+        fields => {
+            let fields = fields.iter().map(|f| {
+                let ident = f.ident.as_ref().unwrap();
+                let key = match GodotAttr::new(&f.attrs).unwrap().rename {
+                    Some(v) => v,
+                    None => LitStr::new(&ident.to_string(), ident.span()),
+                };
+                let ty = &f.ty;
+                quote!((#key, <#ty as #tr>::TYPE))
+            });
+            quote!(TypeInfo::Struct(&[ #(#fields),* ]))
+        }
+    }
+}
+
+/// Named fields are converted into dictionary keyed by field name.
+fn named_struct(fields: &Fields) -> (TokenStream, TokenStream) {
+    let mut get = Vec::new();
+    let mut set = Vec::new();
+    for f in fields.iter() {
+        let ident = f.ident.as_ref().unwrap();
+        let attr = GodotAttr::new(&f.attrs).unwrap();
+        let key = match attr.rename {
+            Some(v) => v,
+            None => LitStr::new(&ident.to_string(), ident.span()),
+        };
+        let default = if attr.default.is_some() {
+            quote!(Some(Default::default))
+        } else {
+            quote!(None)
+        };
+
+        get.push(quote!(#ident: dict_get(&dict, #key, #default)?));
+        set.push(quote!(dict_set(&mut dict, #key, self.#ident)));
+    }
+
+    (
+        quote!(
+            let dict = GodotDictionary::try_from(value)?;
+            Ok(Self { #(#get),* })
+        ),
+        quote!(
+            let mut dict = GodotDictionary::new();
+            #(#set;)*
+            dict.into()
+        ),
+    )
+}
+
+/// Newtype struct is converted as it's inner type.
+fn newtype_struct() -> (TokenStream, TokenStream) {
+    (
+        quote!(FromGodot::from_godot(value).map(Self)),
+        quote!(self.0.into_godot()),
+    )
+}
+
+/// Unnamed fields are converted positionally into array.
+fn tuple_struct(fields: &Fields) -> (TokenStream, TokenStream) {
+    let len = fields.len();
+    let index: Vec<_> = (0..len).map(Index::from).collect();
+
+    (
+        quote!(
+            let a = GodotArray::try_from(value)?;
+            array_check_len(&a, #len)?;
+            Ok(Self( #(array_get(&a, #index)?),* ))
+        ),
+        quote!(GodotArray::from_slice(&[ #(self.#index.into_godot()),* ]).into()),
+    )
+}
+
+/// Enum is converted into it's discriminant.
+fn int_enum(data: &DataEnum) -> (TokenStream, TokenStream) {
+    let variants: Vec<_> = data.variants.iter().map(|v| &v.ident).collect();
+
+    (
+        quote!(
+            match i64::from_godot(value)? {
+                #(v if v == Self::#variants as i64 => Ok(Self::#variants),)*
+                v => Err(ConvertError::InvalidDiscriminant(v)),
+            }
+        ),
+        quote!((self as i64).into_godot()),
+    )
+}
+
+/// Enum is converted into it's (possibly renamed) variant name.
+fn string_enum(data: &DataEnum) -> (TokenStream, TokenStream) {
+    let variants: Vec<_> = data.variants.iter().map(|v| &v.ident).collect();
+    let names: Vec<_> = data
+        .variants
+        .iter()
+        .map(|v| match GodotAttr::new(&v.attrs).unwrap().rename {
+            Some(n) => n,
+            None => LitStr::new(&v.ident.to_string(), v.ident.span()),
+        })
+        .collect();
+
+    (
+        quote!(
+            match &String::from_godot(value)? as &str {
+                #(#names => Ok(Self::#variants),)*
+                v => Err(ConvertError::UnknownVariant(v.into())),
+            }
+        ),
+        quote!(
+            match self {
+                #(Self::#variants => #names,)*
+            }
+            .into_godot()
+        ),
+    )
+}
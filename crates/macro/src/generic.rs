@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::Result as ParseResult;
+use syn::spanned::Spanned;
+use syn::{parse2, FnArg, GenericParam, Generics, ItemFn, Pat, Signature, Type};
+
+use crate::parser::{BindgenFunction, BindgenMetadata};
+use crate::util::join_errors;
+
+/// Generic function exported once per concrete type of `instantiate`.
+///
+/// Every instance is a non-generic wrapper calling the generic function,
+/// which is then exported like any other function.
+pub struct BindgenGeneric {
+    item: ItemFn,
+    instances: Vec<BindgenFunction>,
+}
+
+impl BindgenGeneric {
+    pub fn new(metadata: BindgenMetadata, mut item: ItemFn) -> ParseResult<Self> {
+        let generics = &item.sig.generics;
+        let param = match generics.params.iter().collect::<Vec<_>>()[..] {
+            [GenericParam::Type(t)] => t.ident.clone(),
+            _ => bail_syn!(
+                generics.span(),
+                "Generic function must have a single type parameter"
+            ),
+        };
+
+        let instances = match &metadata.instantiate {
+            Some(v) if !v.is_empty() => v,
+            Some(_) => bail_syn!(generics.span(), "No type to instantiate with"),
+            None => bail_syn!(
+                generics.span(),
+                "Generic function must be instantiated with `instantiate(...)`"
+            ),
+        };
+        if let Some(v) = &metadata.name {
+            bail_syn!(v.span(), "Generic function is named by `instantiate(...)`");
+        }
+        if metadata.start {
+            bail_syn!(generics.span(), "Start function cannot be generic");
+        }
+        let mut names = HashSet::new();
+        if let Some((_, v)) = instances.iter().find(|(_, v)| !names.insert(v.value())) {
+            bail_syn!(v.span(), "Duplicate instance name {:?}", (v.value()));
+        }
+
+        let mut errs = None;
+        let mut ret = Vec::new();
+        for (ty, name) in instances {
+            let metadata = BindgenMetadata {
+                name: Some(name.clone()),
+                instantiate: None,
+                ..metadata.clone()
+            };
+            let v = instance_item(&item, &param, ty)
+                .and_then(|v| BindgenFunction::instance(metadata, v));
+            if let Some(v) = join_errors(&mut errs, v) {
+                ret.push(v);
+            }
+        }
+        if let Some(e) = errs {
+            return Err(e);
+        }
+
+        // Parameter attributes are only meaningful to the instances.
+        for v in item.sig.inputs.iter_mut() {
+            if let FnArg::Typed(v) = v {
+                v.attrs.retain(|a| {
+                    !a.path.is_ident("native")
+                        && !a.path.is_ident("variant")
+                        && !a.path.is_ident("default")
+                });
+            }
+        }
+
+        Ok(Self {
+            item,
+            instances: ret,
+        })
+    }
+}
+
+/// Whether generics have a type parameter, which must be instantiated.
+///
+/// Lifetime and const parameters are left to the compiler.
+pub(crate) fn has_type_param(generics: &Generics) -> bool {
+    generics
+        .params
+        .iter()
+        .any(|v| matches!(v, GenericParam::Type(_)))
+}
+
+/// Replaces every lifetime in `ty` with `'static`.
+///
+/// Types are also named in metadata outside of the function, where its lifetimes are undeclared.
+pub(crate) fn static_lifetimes(ty: &Type) -> Type {
+    fn replace(tokens: TokenStream) -> TokenStream {
+        let mut is_lifetime = false;
+        tokens
+            .into_iter()
+            .map(|t| {
+                let t = match t {
+                    TokenTree::Ident(i) if is_lifetime => {
+                        TokenTree::from(Ident::new("static", i.span()))
+                    }
+                    TokenTree::Group(g) => {
+                        let mut ret = Group::new(g.delimiter(), replace(g.stream()));
+                        ret.set_span(g.span());
+                        ret.into()
+                    }
+                    t => t,
+                };
+                is_lifetime = matches!(&t, TokenTree::Punct(p) if p.as_char() == '\'');
+                t
+            })
+            .collect()
+    }
+
+    parse2(replace(ty.to_token_stream())).unwrap_or_else(|_| ty.clone())
+}
+
+/// Creates wrapper of generic function, with it's type parameter replaced by `ty`.
+fn instance_item(item: &ItemFn, param: &Ident, ty: &Type) -> ParseResult<ItemFn> {
+    let mut sig = item.sig.clone();
+    sig.generics = Generics::default();
+    let mut sig: Signature = parse2(substitute(sig.into_token_stream(), param, ty))?;
+    sig.ident = format_ident!("instance", span = item.sig.ident.span());
+
+    // Parameters are forwarded by name, which is also kept in metadata.
+    let mut args = Vec::new();
+    for (i, v) in sig.inputs.iter_mut().enumerate() {
+        if let FnArg::Typed(v) = v {
+            let ident = match &*v.pat {
+                Pat::Ident(p) => p.ident.clone(),
+                _ => format_ident!("arg{}", i),
+            };
+            v.pat = parse_quote!(#ident);
+            args.push(ident);
+        }
+    }
+
+    let ident = &item.sig.ident;
+    let call = quote!(#ident::<#ty>(#(#args),*));
+    let call = match sig.asyncness {
+        Some(_) => quote!(#call.await),
+        None => call,
+    };
+
+    let attrs = item.attrs.iter().filter(|a| a.path.is_ident("doc"));
+    let vis = &item.vis;
+    parse2(quote!(
+        #(#attrs)*
+        #vis #sig {
+            #call
+        }
+    ))
+}
+
+/// Replaces every occurrence of type parameter with the concrete type.
+fn substitute(tokens: TokenStream, param: &Ident, ty: &Type) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|t| match t {
+            TokenTree::Ident(i) if i == *param => ty.to_token_stream(),
+            TokenTree::Group(g) => {
+                let mut ret = Group::new(g.delimiter(), substitute(g.stream(), param, ty));
+                ret.set_span(g.span());
+                TokenTree::from(ret).into()
+            }
+            t => t.into(),
+        })
+        .collect()
+}
+
+impl ToTokens for BindgenGeneric {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.item.to_tokens(tokens);
+
+        // Every instance is scoped, so their wrappers don't clash.
+        for v in &self.instances {
+            quote!(
+                const _: () = {
+                    #v
+                };
+            )
+            .to_tokens(tokens);
+        }
+    }
+}
@@ -1,5 +1,11 @@
-mod parser;
+#[macro_use]
 mod util;
+mod class;
+mod constant;
+mod convert;
+mod generic;
+mod module;
+mod parser;
 
 extern crate proc_macro;
 #[macro_use]
@@ -8,6 +14,36 @@ extern crate syn;
 use proc_macro::TokenStream;
 use quote::ToTokens;
 
+/// Exports or imports an item through the bindgen.
+///
+/// Supported items are functions, `impl` blocks and structs (as classes), constants,
+/// `extern` blocks (as imports) and inline modules (as namespaces).
+///
+/// # Options
+///
+/// - `use_native_types`: Pass values natively instead of as Godot values.
+/// - `name = "..."`: Exported name of a function or constant.
+/// - `prefix = "..."`: Prefix of every export inside a module.
+/// - `on_error = <expr>`: Value returned when the function returns `Err`.
+/// - `returns = "..."` or `returns = ("...", ...)`: Names of results.
+///   Returned tuple is split into separate results, unless a single name is given,
+///   in which case it is returned as one array. Native tuple is always split.
+/// - `start`: Run the function once after instantiation.
+/// - `instantiate(<type> => "...", ...)`: Export instances of a generic function.
+/// - `no_constructor`: Do not export constructor of a struct.
+///
+/// # Default values
+///
+/// Trailing variant parameters of an exported function or method may have `#[default = <expr>]`.
+/// The CLI then also exports it as `name.N`, taking only the first N arguments.
+/// Both omitted and null arguments take the default value,
+/// so an `Option<T>` parameter with a default other than `None` never receives `None`.
+///
+/// # Imported statics
+///
+/// A `static X: T;` in an `extern` block is fetched from host on first access.
+/// Its type is rewritten into `godot_wasm_bindgen::host_static::HostStatic<T>`,
+/// so the value is read with `X.get()` or `X.with(|v| ...)` instead of `X`.
 #[proc_macro_attribute]
 pub fn godot_wasm_bindgen(metadata: TokenStream, input: TokenStream) -> TokenStream {
     let metadata = parse_macro_input!(metadata as parser::BindgenMetadata);
@@ -23,3 +59,12 @@ pub fn godot_wasm_bindgen(metadata: TokenStream, input: TokenStream) -> TokenStr
     println!("{}", ret);
     ret.into()
 }
+
+#[proc_macro_derive(GodotConvert, attributes(godot))]
+pub fn derive_godot_convert(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match convert::GodotConvert::new(input) {
+        Ok(v) => v.into_token_stream().into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
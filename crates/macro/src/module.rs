@@ -0,0 +1,93 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::Result as ParseResult;
+use syn::spanned::Spanned;
+use syn::{Attribute, Item, ItemMod, LitStr};
+
+use crate::parser::BindgenMetadata;
+
+/// Module whose bindgen items are exported with a common prefix.
+pub struct BindgenModule {
+    item: ItemMod,
+}
+
+impl BindgenModule {
+    pub fn new(metadata: BindgenMetadata, mut item: ItemMod) -> ParseResult<Self> {
+        let BindgenMetadata {
+            use_native_types,
+            no_constructor,
+            on_error,
+            name,
+            prefix,
+            returns,
+            start,
+            instantiate,
+        } = metadata;
+        if use_native_types
+            || no_constructor
+            || on_error.is_some()
+            || name.is_some()
+            || returns.is_some()
+            || start
+            || instantiate.is_some()
+        {
+            bail_syn!(item.span(), "Only prefix is supported on module");
+        }
+
+        let items = match &mut item.content {
+            Some((_, v)) => v,
+            None => bail_syn!(item.span(), "Module must be inline"),
+        };
+        let prefix = LitStr::new(&prefix, item.ident.span());
+        add_prefix(items, &prefix);
+
+        Ok(Self { item })
+    }
+}
+
+/// Prepends `prefix` into every bindgen attribute of `items`.
+fn add_prefix(items: &mut [Item], prefix: &LitStr) {
+    for i in items.iter_mut() {
+        let attrs = match i {
+            Item::Fn(v) => &mut v.attrs,
+            // Imports are named by the host, so they are not prefixed.
+            Item::Impl(v) => &mut v.attrs,
+            Item::Struct(v) => &mut v.attrs,
+            Item::Const(v) => &mut v.attrs,
+            Item::Mod(v) => {
+                if !v.attrs.iter().any(is_bindgen) {
+                    // Plain submodule, it's bindgen items inherit the prefix.
+                    if let Some((_, items)) = &mut v.content {
+                        add_prefix(items, prefix);
+                    }
+                    continue;
+                }
+                &mut v.attrs
+            }
+            _ => continue,
+        };
+
+        for a in attrs.iter_mut().filter(|a| is_bindgen(a)) {
+            a.tokens = match syn::parse2::<proc_macro2::Group>(a.tokens.clone()) {
+                Ok(g) => {
+                    let inner = g.stream();
+                    quote!((prefix = #prefix, #inner))
+                }
+                Err(_) => quote!((prefix = #prefix)),
+            };
+        }
+    }
+}
+
+fn is_bindgen(attr: &Attribute) -> bool {
+    match attr.path.segments.last() {
+        Some(v) => v.ident == "godot_wasm_bindgen",
+        None => false,
+    }
+}
+
+impl ToTokens for BindgenModule {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.item.to_tokens(tokens);
+    }
+}
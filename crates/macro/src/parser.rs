@@ -1,25 +1,36 @@
 use std::iter;
 
 use proc_macro2::{Span, TokenStream};
-use quote::{format_ident, quote, ToTokens};
-use syn::parse::{Error as ParseError, Parse, ParseStream, Result as ParseResult};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::parse::{Error as ParseError, Parse, ParseStream, Parser, Result as ParseResult};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{
-    parse2, FnArg, ForeignItem, ForeignItemFn, Ident, Index, Item, ItemFn, ItemForeignMod, LitByte,
-    LitInt, LitStr, ReturnType, Signature, Type, Visibility,
+    parenthesized, parse2, token, Attribute, Expr, FnArg, ForeignItem, ForeignItemFn,
+    ForeignItemStatic, GenericArgument, Ident, Index, Item, ItemFn, ItemForeignMod, Lit, LitByte,
+    LitStr, Meta, MetaNameValue, Pat, PatType, PathArguments, ReturnType, Signature, Type,
+    Visibility,
 };
 
-use crate::util::{join_errors, prepend, tag_length};
+use crate::class::{BindgenImpl, BindgenStruct};
+use crate::constant::BindgenConst;
+use crate::generic::{has_type_param, static_lifetimes, BindgenGeneric};
+use crate::module::BindgenModule;
+use crate::util::join_errors;
 
-macro_rules! bail_syn {
-    ($span:expr, $fmt:literal $(, $v:tt)* $(,)?) => {
-        return Err(syn::parse::Error::new($span, format!($fmt $(, $v)*)))
-    };
-}
-
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct BindgenMetadata {
-    use_native_types: bool,
+    pub(crate) use_native_types: bool,
+    pub(crate) no_constructor: bool,
+    pub(crate) on_error: Option<Expr>,
+    pub(crate) name: Option<LitStr>,
+    pub(crate) prefix: String,
+    /// Names of results, in metadata.
+    pub(crate) returns: Option<Vec<LitStr>>,
+    /// Function is run once after instantiation.
+    pub(crate) start: bool,
+    /// Concrete types of generic function, with their export names.
+    pub(crate) instantiate: Option<Vec<(Type, LitStr)>>,
 }
 
 #[derive(Default)]
@@ -42,6 +53,90 @@ impl BindgenMetatadaBuilder {
             )),
         }
     }
+
+    fn on_error(&mut self, span: Span, value: Expr) -> ParseResult<&mut Self> {
+        match &mut self.0.on_error {
+            v @ None => {
+                *v = Some(value);
+                Ok(self)
+            }
+            Some(_) => Err(ParseError::new(
+                span,
+                "Attribute already set (maybe duplicate?)",
+            )),
+        }
+    }
+
+    fn name(&mut self, span: Span, value: LitStr) -> ParseResult<&mut Self> {
+        match &mut self.0.name {
+            v @ None => {
+                *v = Some(value);
+                Ok(self)
+            }
+            Some(_) => Err(ParseError::new(
+                span,
+                "Attribute already set (maybe duplicate?)",
+            )),
+        }
+    }
+
+    fn returns(&mut self, span: Span, value: Vec<LitStr>) -> ParseResult<&mut Self> {
+        match &mut self.0.returns {
+            v @ None => {
+                *v = Some(value);
+                Ok(self)
+            }
+            Some(_) => Err(ParseError::new(
+                span,
+                "Attribute already set (maybe duplicate?)",
+            )),
+        }
+    }
+
+    fn instantiate(&mut self, span: Span, value: Vec<(Type, LitStr)>) -> ParseResult<&mut Self> {
+        match &mut self.0.instantiate {
+            v @ None => {
+                *v = Some(value);
+                Ok(self)
+            }
+            Some(_) => Err(ParseError::new(
+                span,
+                "Attribute already set (maybe duplicate?)",
+            )),
+        }
+    }
+
+    /// Prefixes are concatenated, so nested modules stack their prefix.
+    fn prefix(&mut self, value: LitStr) -> ParseResult<&mut Self> {
+        self.0.prefix.push_str(&value.value());
+        Ok(self)
+    }
+
+    fn start(&mut self, span: Span) -> ParseResult<&mut Self> {
+        match &mut self.0.start {
+            v @ false => {
+                *v = true;
+                Ok(self)
+            }
+            true => Err(ParseError::new(
+                span,
+                "Attribute already set (maybe duplicate?)",
+            )),
+        }
+    }
+
+    fn no_constructor(&mut self, span: Span) -> ParseResult<&mut Self> {
+        match &mut self.0.no_constructor {
+            v @ false => {
+                *v = true;
+                Ok(self)
+            }
+            true => Err(ParseError::new(
+                span,
+                "Attribute already set (maybe duplicate?)",
+            )),
+        }
+    }
 }
 
 impl Parse for BindgenMetadata {
@@ -73,6 +168,67 @@ impl Parse for BindgenMetadata {
 
             match &attr_name as &str {
                 "use_native_types" => join_errors(&mut errs, ret.use_native_types(attr_span)),
+                "no_constructor" => join_errors(&mut errs, ret.no_constructor(attr_span)),
+                "start" => join_errors(&mut errs, ret.start(attr_span)),
+                "on_error" => {
+                    let v = input
+                        .parse::<Token![=]>()
+                        .and_then(|_| input.parse::<Expr>());
+                    match join_errors(&mut errs, v) {
+                        Some(v) => join_errors(&mut errs, ret.on_error(attr_span, v)),
+                        None => break,
+                    }
+                }
+                "name" => {
+                    let v = input
+                        .parse::<Token![=]>()
+                        .and_then(|_| input.parse::<LitStr>());
+                    match join_errors(&mut errs, v) {
+                        Some(v) => join_errors(&mut errs, ret.name(attr_span, v)),
+                        None => break,
+                    }
+                }
+                "returns" => {
+                    let v = input.parse::<Token![=]>().and_then(|_| {
+                        if input.peek(token::Paren) {
+                            let content;
+                            parenthesized!(content in input);
+                            let v = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                            Ok(v.into_iter().collect())
+                        } else {
+                            Ok(vec![input.parse::<LitStr>()?])
+                        }
+                    });
+                    match join_errors(&mut errs, v) {
+                        Some(v) => join_errors(&mut errs, ret.returns(attr_span, v)),
+                        None => break,
+                    }
+                }
+                "instantiate" => {
+                    let v = (|| {
+                        let content;
+                        parenthesized!(content in input);
+                        let v = Punctuated::<_, Token![,]>::parse_terminated_with(&content, |i| {
+                            let ty = i.parse::<Type>()?;
+                            i.parse::<Token![=>]>()?;
+                            Ok((ty, i.parse::<LitStr>()?))
+                        })?;
+                        Ok(v.into_iter().collect())
+                    })();
+                    match join_errors(&mut errs, v) {
+                        Some(v) => join_errors(&mut errs, ret.instantiate(attr_span, v)),
+                        None => break,
+                    }
+                }
+                "prefix" => {
+                    let v = input
+                        .parse::<Token![=]>()
+                        .and_then(|_| input.parse::<LitStr>());
+                    match join_errors(&mut errs, v) {
+                        Some(v) => join_errors(&mut errs, ret.prefix(v)),
+                        None => break,
+                    }
+                }
                 _ => {
                     if input.peek(Token![=]) {
                         join_errors(&mut errs, input.parse::<Token![=]>());
@@ -108,67 +264,170 @@ pub struct BindgenFunction {
     item: ItemFn,
 
     name: String,
-    param_count: usize,
+    output: ReturnType,
+    is_result: bool,
+    native_params: Vec<bool>,
+    /// Default values of optional parameters.
+    defaults: Vec<Option<Expr>>,
     result_count: usize,
+    /// Instance of generic function, which is always exported through shim.
+    is_instance: bool,
 }
 
 impl BindgenFunction {
-    pub fn new(metadata: BindgenMetadata, mut item: ItemFn) -> ParseResult<Self> {
+    pub fn new(metadata: BindgenMetadata, item: ItemFn) -> ParseResult<Self> {
+        if let Some((t, _)) = metadata.instantiate.iter().flatten().next() {
+            bail_syn!(t.span(), "Only generic function can be instantiated");
+        }
+
+        Self::with_item(metadata, item, false)
+    }
+
+    /// Exports instance of generic function.
+    pub(crate) fn instance(metadata: BindgenMetadata, item: ItemFn) -> ParseResult<Self> {
+        Self::with_item(metadata, item, true)
+    }
+
+    fn with_item(
+        metadata: BindgenMetadata,
+        mut item: ItemFn,
+        is_instance: bool,
+    ) -> ParseResult<Self> {
         if !matches!(&item.vis, Visibility::Public(_)) {
             bail_syn!(item.vis.span(), "Visibility must be public");
         }
-
-        if metadata.use_native_types {
-            if item.sig.abi.is_none() {
-                return Err(ParseError::new_spanned(
-                    item.sig,
-                    "Natively exporting functions must be marked as extern",
-                ));
-            }
-        } else {
-            item.vis = Visibility::Inherited;
+        if has_type_param(&item.sig.generics) {
+            bail_syn!(
+                item.sig.generics.span(),
+                "Generic function must be instantiated with `instantiate(...)`"
+            );
+        }
+        if metadata.no_constructor {
+            bail_syn!(item.span(), "Only exported struct can disable constructor");
         }
 
         let name;
-        let (param_count, result_count);
+        let (output, is_result);
+        let (native_params, defaults, result_count);
         {
-            let sig = &item.sig;
-            sig.inputs
-                .iter()
-                .map(|t| match t {
-                    FnArg::Typed(_) => Ok(()),
-                    FnArg::Receiver(t) => {
-                        bail_syn!(t.span(), "Support for methods is not currently supported")
-                    }
-                })
-                .fold(Ok(()), |a, b| match (a, b) {
-                    (Ok(()), x) | (x, Ok(())) => x,
-                    (Err(mut e1), Err(e2)) => {
-                        e1.combine(e2);
-                        Err(e1)
+            let sig = &mut item.sig;
+            let mut errs = None;
+            let mut params = Vec::new();
+            let mut param_defaults = Vec::new();
+            for t in sig.inputs.iter_mut() {
+                match t {
+                    FnArg::Receiver(t) => join_errors::<()>(
+                        &mut errs,
+                        Err(ParseError::new(
+                            t.span(),
+                            "Methods must be exported from a bindgen impl block",
+                        )),
+                    ),
+                    FnArg::Typed(t) => {
+                        let v = take_param_mode(&mut t.attrs, metadata.use_native_types).and_then(
+                            |n| {
+                                let has_optional = param_defaults.iter().any(Option::is_some);
+                                Ok((n, take_param_default(t, n, has_optional)?))
+                            },
+                        );
+                        join_errors(&mut errs, v).map(|(n, d)| {
+                            params.push(n);
+                            param_defaults.push(d);
+                        })
                     }
-                })?;
-
-            name = sig.ident.to_string();
+                };
+            }
+            if let Some(e) = errs {
+                return Err(e);
+            }
 
-            param_count = sig.inputs.len();
-            result_count = match &sig.output {
-                ReturnType::Default => 0,
-                ReturnType::Type(_, t) => match &**t {
-                    Type::Tuple(t) => t.elems.len(),
-                    _ => 1,
-                },
+            name = match &metadata.name {
+                Some(v) => format!("{}{}", metadata.prefix, v.value()),
+                None => format!("{}{}", metadata.prefix, sig.ident),
             };
+
+            match unwrap_result(&sig.output) {
+                Some(v) => {
+                    output = v;
+                    is_result = true;
+                }
+                None => {
+                    output = sig.output.clone();
+                    is_result = false;
+                }
+            }
+
+            if metadata.use_native_types {
+                check_native_output(&output, false)?;
+                if is_result {
+                    check_native_result(&metadata, &sig.output, &output)?;
+                }
+            }
+
+            native_params = params;
+            defaults = param_defaults;
+            result_count = self::result_count(&output, split_results(&metadata));
+            check_returns(metadata.returns.as_ref(), result_count)?;
+
+            if sig.asyncness.is_some() {
+                check_async(&metadata, sig)?;
+            }
+
+            if metadata.start {
+                if !sig.inputs.is_empty() {
+                    bail_syn!(sig.inputs.span(), "Start function cannot have parameters");
+                }
+                if let ReturnType::Type(_, t) = &output {
+                    bail_syn!(t.span(), "Start function cannot return value");
+                }
+            }
         }
 
-        Ok(Self {
+        let mut ret = Self {
             metadata,
             item,
 
             name,
-            param_count,
+            output,
+            is_result,
+            native_params,
+            defaults,
             result_count,
-        })
+            is_instance,
+        };
+
+        if ret.has_shim() {
+            // Shim is the exported function, so the item itself must not be exported.
+            ret.item.vis = Visibility::Inherited;
+            ret.item.sig.abi = None;
+            ret.item
+                .attrs
+                .retain(|a| !a.path.is_ident("no_mangle") && !a.path.is_ident("export_name"));
+        } else if ret.item.sig.abi.is_none() {
+            return Err(ParseError::new_spanned(
+                &ret.item.sig,
+                "Natively exporting functions must be marked as extern",
+            ));
+        }
+
+        Ok(ret)
+    }
+
+    /// Natively exported function without any conversion or renaming is exported as is.
+    fn has_shim(&self) -> bool {
+        !self.metadata.use_native_types
+            || self.is_instance
+            || self.metadata.name.is_some()
+            || !self.metadata.prefix.is_empty()
+            || self.metadata.start
+            || self.item.sig.asyncness.is_some()
+            || self.is_result
+            || self.native_params.contains(&false)
+            || self.item.sig.inputs.iter().any(|v| match v {
+                FnArg::Typed(v) => lowered_type(&v.ty).is_some(),
+                FnArg::Receiver(_) => false,
+            })
+            || matches!(&self.output, ReturnType::Type(_, t) if lowered_type(t).is_some())
     }
 }
 
@@ -178,140 +437,882 @@ impl ToTokens for BindgenFunction {
 
         let name = &self.name;
         let sig = &self.item.sig;
+        let native = self.metadata.use_native_types;
 
-        let func_export = if self.metadata.use_native_types {
-            quote!()
-        } else {
-            let param_args = (0..self.param_count).map(|i| {
-                let i = format_ident!("arg{}", i);
-                quote!(#i : GodotValue)
-            });
+        let inputs = || {
+            sig.inputs
+                .iter()
+                .zip(self.native_params.iter().zip(self.defaults.iter()))
+                .enumerate()
+                .map(|(i, (v, (&n, d)))| match v {
+                    FnArg::Typed(v) => (i, v, n, d.as_ref()),
+                    FnArg::Receiver(_) => unreachable!("Method function should get filtered"),
+                })
+        };
 
-            let result_args = match &sig.output {
-                v @ ReturnType::Default => quote!(#v),
-                ReturnType::Type(arrow, t) => match &**t {
-                    Type::Tuple(t) => {
-                        let r = iter::repeat(Ident::new("GodotValue", Span::call_site()))
-                            .take(t.elems.len());
-                        quote!(#arrow ( #(#r ,)* ))
-                    }
-                    _ => quote!(#arrow GodotValue),
-                },
+        let is_async = sig.asyncness.is_some();
+
+        let func_export = if is_async {
+            let param_args = inputs().map(|(i, v, n, _)| param_arg(&v.ty, i, n));
+            let param_cvt = inputs().map(|(i, v, n, d)| param_cvt(&v.ty, i, n, d));
+
+            // Task result is a single value, so tuple is converted into array.
+            let ret_cvt = match &self.output {
+                ReturnType::Default => quote!(GodotValue::null()),
+                ReturnType::Type(..) => quote!(IntoGodot::into_godot(ret)),
+            };
+            let err_cvt = if self.is_result {
+                result_err_cvt(
+                    self.metadata.on_error.as_ref(),
+                    quote!(return GodotValue::null()),
+                )
+            } else {
+                quote!()
             };
 
-            let param_cvt = (0..self.param_count).map(|i| {
-                let i = format_ident!("arg{}", i);
-                quote!(#i.try_into().unwrap())
-            });
+            let name_ident = &sig.ident;
+            quote!(
+                #[export_name = #name]
+                #[doc(hidden)]
+                pub extern "C" fn export_function(#(#param_args),*) -> u32 {
+                    // Parameters are converted before the task is spawned.
+                    let future = #name_ident ( #(#param_cvt),* );
+                    spawn_task(async move {
+                        let ret = future.await;
+                        #err_cvt
+                        #ret_cvt
+                    })
+                }
+            )
+        } else if self.has_shim() {
+            let param_args = inputs().map(|(i, v, n, _)| param_arg(&v.ty, i, n));
+            let param_cvt = inputs().map(|(i, v, n, d)| param_cvt(&v.ty, i, n, d));
 
-            let ret_cvt = match &sig.output {
-                ReturnType::Default => quote!(ret),
-                ReturnType::Type(_, t) => match &**t {
-                    Type::Tuple(t) => {
-                        let r = (0..t.elems.len()).map(Index::from);
-                        quote!( ( #(ret.#r.into() ,)* ) )
-                    }
-                    _ => quote!(ret.into()),
-                },
+            let (result_args, ret_cvt, fallback) = if native {
+                let (result_args, ret_cvt) = native_output(&self.output);
+                (result_args, ret_cvt, quote!(Default::default()))
+            } else {
+                let split = split_results(&self.metadata);
+                let null = variant_null(&self.output, split);
+                (
+                    variant_output(&self.output, split),
+                    variant_result_cvt(&self.output, split),
+                    quote!(return #null),
+                )
+            };
+            let err_cvt = if self.is_result {
+                result_err_cvt(self.metadata.on_error.as_ref(), fallback)
+            } else {
+                quote!()
             };
 
-            let name_ident = format_ident!("{}", name);
+            let name_ident = &sig.ident;
             quote!(
                 #[export_name = #name]
                 #[doc(hidden)]
                 pub extern "C" fn export_function(#(#param_args),*) #result_args {
-                    use std::convert::TryFrom;
                     let ret = #name_ident ( #(#param_cvt),* );
+                    #err_cvt
                     #ret_cvt
                 }
             )
+        } else {
+            quote!()
         };
 
-        let mut bytes: Vec<u8> = Vec::new();
-        leb128::write::unsigned(&mut bytes, 64).unwrap();
-        leb128::write::unsigned(&mut bytes, name.len() as _).unwrap();
-        bytes.extend(name.as_bytes());
+        if self.metadata.start {
+            let mut bytes: Vec<u8> = Vec::new();
+            leb128::write::unsigned(&mut bytes, 67).unwrap();
+            write_name(&mut bytes, name);
 
-        let (param_len, param_bcount) = {
-            let mut bytes = Vec::new();
-            leb128::write::unsigned(&mut bytes, self.param_count as _).unwrap();
+            let data = raw_symbol_data(bytes);
+
+            quote!(
+                const _: () = {
+                    use godot_wasm_bindgen::__hidden::report_error;
+
+                    #func_export
+
+                    #data
+                };
+            )
+            .to_tokens(tokens);
+            return;
+        }
 
-            let l = bytes.len();
-            let ret = bytes
-                .into_iter()
-                .map(|b| LitByte::new(b, Span::call_site()));
-            (ret, l)
+        let mut bytes: Vec<u8> = Vec::new();
+        leb128::write::unsigned(&mut bytes, if is_async { 69 } else { 64 }).unwrap();
+        write_name(&mut bytes, name);
+
+        let param_checks = inputs().map(|(i, v, n, _)| param_check(&v.pat, i, n));
+        let param_data = inputs()
+            .map(|(i, v, n, _)| param_data(&v.ty, i, n))
+            .collect();
+        let result_data = if is_async {
+            vec![native_data(&parse_quote!(u32))]
+        } else if native {
+            native_output_data(&self.output)
+        } else {
+            variant_data(&self.output, false, split_results(&self.metadata))
         };
-        let (result_len, result_bcount) = {
-            let mut bytes = Vec::new();
-            leb128::write::unsigned(&mut bytes, self.result_count as _).unwrap();
 
-            let l = bytes.len();
-            let ret = bytes
-                .into_iter()
-                .map(|b| LitByte::new(b, Span::call_site()));
-            (ret, l)
+        let info = SymbolInfo {
+            param_names: inputs().map(|(i, v, _, _)| param_name(&v.pat, i)).collect(),
+            result_names: if is_async {
+                vec!["task".into()]
+            } else {
+                result_names(self.metadata.returns.as_ref(), self.result_count)
+            },
+            optional: self.defaults.iter().filter(|v| v.is_some()).count(),
+            doc: doc_string(&self.item.attrs),
         };
 
-        let extra_count = param_bcount + result_bcount + self.param_count + self.result_count;
-        bytes.extend(iter::repeat(0).take(extra_count));
-        tag_length(&mut bytes);
-        bytes.truncate(bytes.len() - extra_count);
-        prepend(&mut bytes, &[1, 0, 0, 0]);
-
-        let bytes_len_token =
-            LitInt::new(&format!("{}", bytes.len() + extra_count), Span::call_site());
-        let bytes_token = bytes
-            .into_iter()
-            .map(|b| LitByte::new(b, Span::call_site()));
-
-        let param_data = if !self.metadata.use_native_types {
-            let v =
-                iter::repeat(Ident::new("GodotValue", Span::call_site())).take(self.param_count);
-            quote!(#(<DataTypeValue<#v>>::value() ,)*)
+        let data = symbol_data(bytes, param_data, result_data, &info);
+
+        quote!(
+            const _: () = {
+                use godot_wasm_bindgen::__hidden::{
+                    from_godot, into_raw_string, into_raw_vec, raw_string, raw_vec, report_error,
+                    spawn_task, FromGodot, GodotValue, IntoGodot, NativeType, TypeInfo,
+                };
+
+                #(#param_checks)*
+
+                #func_export
+
+                #data
+            };
+        )
+        .to_tokens(tokens);
+    }
+}
+
+/// Checks if imported parameter is a borrowed value, which is passed without transferring ownership.
+///
+/// Borrowed value is passed as a handle in both native and variant mode.
+fn is_borrowed_value(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) if r.mutability.is_none() => match &*r.elem {
+            Type::Path(p) if p.qself.is_none() => match p.path.segments.last() {
+                Some(v) if v.arguments.is_empty() => matches!(
+                    &v.ident.to_string() as &str,
+                    "GodotValue" | "GodotArray" | "GodotDictionary" | "GodotString" | "StringArray"
+                ),
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Gets the value of `#[link_name = "..."]`.
+fn link_name(attrs: &[Attribute]) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("link_name"))
+        .find_map(|a| match a.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(v), ..
+            })) => Some(v.value()),
+            _ => None,
+        })
+}
+
+/// Return type of the getter of imported static.
+fn static_output(s: &ForeignItemStatic) -> ReturnType {
+    ReturnType::Type(Token![->](s.colon_token.span), s.ty.clone())
+}
+
+/// Checks if async function can be exported.
+///
+/// Async function is spawned as a task, so it must not borrow it's parameters.
+fn check_async(metadata: &BindgenMetadata, sig: &Signature) -> ParseResult<()> {
+    if metadata.use_native_types {
+        bail_syn!(sig.asyncness.span(), "Async function must return variant");
+    }
+    if metadata.start {
+        bail_syn!(sig.asyncness.span(), "Start function cannot be async");
+    }
+    if let Some(v) = metadata.returns.iter().flatten().next() {
+        bail_syn!(v.span(), "Result names cannot be set on async function");
+    }
+
+    for v in sig.inputs.iter() {
+        if let FnArg::Typed(v) = v {
+            if matches!(lowered_type(&v.ty), Some(l) if l.is_borrowed()) {
+                bail_syn!(v.ty.span(), "Async function cannot borrow parameter");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks if static can be imported.
+fn check_static(s: &ForeignItemStatic, native: bool) -> ParseResult<()> {
+    if let Some(v) = &s.mutability {
+        bail_syn!(v.span(), "Mutable static cannot be imported");
+    }
+    if let Type::Tuple(t) = &*s.ty {
+        bail_syn!(t.span(), "Tuple static cannot be imported");
+    }
+    if native {
+        check_native_output(&static_output(s), true)?;
+    }
+
+    Ok(())
+}
+
+/// Converts result of import into it's Rust type.
+fn import_result_cvt(output: &ReturnType, native: bool) -> TokenStream {
+    match output {
+        ReturnType::Type(_, t) if native => match lowered_type(t) {
+            Some(LoweredType::String) => quote!(raw_string(ret.0, ret.1)),
+            Some(_) => quote!(raw_vec(ret.0, ret.1)),
+            None => quote!(ret),
+        },
+        _ if native => quote!(ret),
+        ReturnType::Default => quote!(ret),
+        ReturnType::Type(_, t) => match &**t {
+            Type::Tuple(t) => {
+                let r = (0..t.elems.len()).map(Index::from);
+                let n = (0..t.elems.len()).map(|i| format!("return value {}", i));
+                quote!( ( #(from_godot(ret.#r, #n) ,)* ) )
+            }
+            _ => quote!(from_godot(ret, "return value")),
+        },
+    }
+}
+
+/// Takes `#[native]` or `#[variant]` attribute off a parameter.
+///
+/// Returns whether the parameter is passed natively.
+pub(crate) fn take_param_mode(attrs: &mut Vec<Attribute>, default: bool) -> ParseResult<bool> {
+    let mut ret = None;
+    let mut errs = None;
+    attrs.retain(|a| {
+        let v = if a.path.is_ident("native") {
+            true
+        } else if a.path.is_ident("variant") {
+            false
         } else {
-            let v = sig.inputs.iter().map(|v| match &*v {
-                FnArg::Typed(v) => &*v.ty,
-                FnArg::Receiver(_) => unreachable!("Method function should get filtered"),
-            });
-            quote!(#(<DataTypeValue<#v>>::value() ,)*)
+            return true;
         };
 
-        let result_data = if !self.metadata.use_native_types {
-            let v =
-                iter::repeat(Ident::new("GodotValue", Span::call_site())).take(self.result_count);
-            quote!(#(<DataTypeValue<#v>>::value() ,)*)
+        let r = if !a.tokens.is_empty() {
+            Err(ParseError::new_spanned(&a.tokens, "Unexpected arguments"))
+        } else if ret.is_some() {
+            Err(ParseError::new_spanned(
+                a,
+                "Attribute already set (maybe duplicate?)",
+            ))
         } else {
-            match &sig.output {
-                ReturnType::Default => quote!(),
-                ReturnType::Type(_, v) => match &**v {
-                    Type::Tuple(v) => {
-                        let v = v.elems.iter();
-                        quote!(#(<DataTypeValue<#v>>::value() ,)*)
+            ret = Some(v);
+            Ok(())
+        };
+        join_errors(&mut errs, r);
+        false
+    });
+
+    match errs {
+        Some(e) => Err(e),
+        None => Ok(ret.unwrap_or(default)),
+    }
+}
+
+/// Takes `#[default = expr]` attribute off a parameter.
+///
+/// Parameter with default value is optional, so it must be passed as variant
+/// and be followed only by other optional parameters.
+pub(crate) fn take_param_default(
+    param: &mut PatType,
+    native: bool,
+    has_optional: bool,
+) -> ParseResult<Option<Expr>> {
+    let mut ret = None;
+    let mut errs = None;
+    param.attrs.retain(|a| {
+        if !a.path.is_ident("default") {
+            return true;
+        }
+
+        let parser = |input: ParseStream| {
+            input.parse::<Token![=]>()?;
+            input.parse::<Expr>()
+        };
+        let r = match parser.parse2(a.tokens.clone()) {
+            Ok(_) if ret.is_some() => Err(ParseError::new_spanned(
+                a,
+                "Attribute already set (maybe duplicate?)",
+            )),
+            Ok(v) => {
+                ret = Some(v);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        join_errors(&mut errs, r);
+        false
+    });
+
+    if let Some(e) = errs {
+        return Err(e);
+    }
+    match &ret {
+        Some(v) if native => bail_syn!(v.span(), "Default value requires variant parameter"),
+        None if has_optional => bail_syn!(
+            param.span(),
+            "Parameter following an optional parameter must have default value"
+        ),
+        _ => Ok(ret),
+    }
+}
+
+/// Native type passed as pointer and length pair.
+pub(crate) enum LoweredType<'a> {
+    Str,
+    String,
+    Slice(&'a Type),
+    Vec(&'a Type),
+}
+
+impl LoweredType<'_> {
+    /// Type of the array element.
+    pub(crate) fn elem(&self) -> TokenStream {
+        match self {
+            Self::Str | Self::String => quote!(u8),
+            Self::Slice(t) | Self::Vec(t) => quote!(#t),
+        }
+    }
+
+    pub(crate) fn is_borrowed(&self) -> bool {
+        matches!(self, Self::Str | Self::Slice(_))
+    }
+}
+
+pub(crate) fn lowered_type(ty: &Type) -> Option<LoweredType<'_>> {
+    match ty {
+        Type::Reference(r) if r.mutability.is_none() => match &*r.elem {
+            Type::Path(p) if p.qself.is_none() && p.path.is_ident("str") => Some(LoweredType::Str),
+            Type::Slice(s) => Some(LoweredType::Slice(&s.elem)),
+            _ => None,
+        },
+        Type::Path(p) if p.qself.is_none() => {
+            let seg = p.path.segments.last()?;
+            match &seg.arguments {
+                PathArguments::None if seg.ident == "String" => Some(LoweredType::String),
+                PathArguments::AngleBracketed(a) if seg.ident == "Vec" && a.args.len() == 1 => {
+                    match a.args.first()? {
+                        GenericArgument::Type(t) => Some(LoweredType::Vec(t)),
+                        _ => None,
                     }
-                    v => quote!(<DataTypeValue<#v>>::value(),),
-                },
+                }
+                _ => None,
             }
+        }
+        _ => None,
+    }
+}
+
+/// Shim parameter, `native` parameter is passed as is.
+pub(crate) fn param_arg(ty: &Type, i: usize, native: bool) -> TokenStream {
+    let ident = format_ident!("arg{}", i);
+    if !native {
+        return quote!(#ident : GodotValue);
+    }
+
+    match lowered_type(ty) {
+        Some(l) => {
+            let (ptr, len) = (format_ident!("arg{}_ptr", i), format_ident!("arg{}_len", i));
+            let elem = l.elem();
+            quote!(#ptr : *mut #elem, #len : u32)
+        }
+        None => quote!(#ident : #ty),
+    }
+}
+
+/// Converts shim parameter into it's actual type.
+///
+/// Null value of optional parameter is substituted with it's `default`.
+pub(crate) fn param_cvt(ty: &Type, i: usize, native: bool, default: Option<&Expr>) -> TokenStream {
+    if !native {
+        let cvt = variant_param_cvt(ty, i);
+        return match default {
+            Some(v) => {
+                let ident = format_ident!("arg{}", i);
+                quote!(if #ident.is_null() { #v } else { #cvt })
+            }
+            None => cvt,
         };
+    }
 
-        quote!(
-            const _: () = {
-                use godot_wasm_bindgen::__hidden::{DataTypeValue, GodotValue};
+    let (ptr, len) = (format_ident!("arg{}_ptr", i), format_ident!("arg{}_len", i));
+    match lowered_type(ty) {
+        Some(LoweredType::Str) => quote!(&unsafe { raw_string(#ptr, #len) }),
+        Some(LoweredType::String) => quote!(unsafe { raw_string(#ptr, #len) }),
+        Some(LoweredType::Slice(_)) => quote!(&unsafe { raw_vec(#ptr, #len) }),
+        Some(LoweredType::Vec(_)) => quote!(unsafe { raw_vec(#ptr, #len) }),
+        None => {
+            let i = format_ident!("arg{}", i);
+            quote!(#i)
+        }
+    }
+}
 
-                #func_export
+/// Checks that failure of natively exported function can be told apart from a valid value.
+///
+/// No native value marks the failure, so a value must be chosen with `on_error`.
+pub(crate) fn check_native_result(
+    metadata: &BindgenMetadata,
+    ret: &ReturnType,
+    output: &ReturnType,
+) -> ParseResult<()> {
+    if metadata.on_error.is_none() && !matches!(output, ReturnType::Default) {
+        bail_syn!(
+            ret.span(),
+            "Natively exported function cannot return Result without `on_error = ...`"
+        );
+    }
 
-                #[link_section = "__godot_wasm_bindgen_data"]
-                #[doc(hidden)]
-                static DATA: [u8; #bytes_len_token] = [
-                    #(#bytes_token ,)*
-                    #(#param_len ,)*
-                    #param_data
-                    #(#result_len ,)*
-                    #result_data
-                ];
+    Ok(())
+}
+
+/// Checks if lowered type is used where it's unsupported.
+pub(crate) fn check_native_output(output: &ReturnType, is_import: bool) -> ParseResult<()> {
+    let t = match output {
+        ReturnType::Type(_, t) => &**t,
+        ReturnType::Default => return Ok(()),
+    };
+
+    match t {
+        Type::Tuple(t) => {
+            for t in t.elems.iter() {
+                if lowered_type(t).is_some() {
+                    bail_syn!(t.span(), "String or array cannot be returned in a tuple");
+                }
+            }
+        }
+        t if is_import && matches!(lowered_type(t), Some(l) if l.is_borrowed()) => {
+            bail_syn!(t.span(), "Imported function cannot return borrowed value")
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Output type and conversion of natively exported shim.
+///
+/// Lowered type is returned as pointer and length pair, which is freed by host.
+pub(crate) fn native_output(output: &ReturnType) -> (TokenStream, TokenStream) {
+    let (arrow, t) = match output {
+        ReturnType::Type(arrow, t) => (arrow, &**t),
+        ReturnType::Default => return (quote!(), quote!(ret)),
+    };
+
+    match lowered_type(t) {
+        Some(l) => {
+            let elem = l.elem();
+            let ret_cvt = match l {
+                LoweredType::Str => quote!(into_raw_string(ret.to_owned())),
+                LoweredType::String => quote!(into_raw_string(ret)),
+                LoweredType::Slice(_) => quote!(into_raw_vec(ret.to_vec())),
+                LoweredType::Vec(_) => quote!(into_raw_vec(ret)),
             };
+            (quote!(#arrow (*mut #elem, u32)), ret_cvt)
+        }
+        None => (quote!(#output), quote!(ret)),
+    }
+}
+
+/// Data type of imported parameter.
+pub(crate) fn mode_data(ty: &Type, native: bool) -> TokenStream {
+    if native {
+        native_data(ty)
+    } else {
+        value_data(ty, true)
+    }
+}
+
+/// Gets `T` of `Result<T, E>` return type.
+///
+/// The type is detected by name, since macro cannot resolve paths.
+/// Any path ending in `Result` with one or two generic arguments is treated as `Result`,
+/// so aliases like `io::Result<T>` work, but a user type named `Result` must not be returned.
+pub(crate) fn unwrap_result(output: &ReturnType) -> Option<ReturnType> {
+    let (arrow, p) = match output {
+        ReturnType::Type(arrow, t) => match &**t {
+            Type::Path(p) if p.qself.is_none() => (arrow, p),
+            _ => return None,
+        },
+        ReturnType::Default => return None,
+    };
+
+    let seg = p.path.segments.last()?;
+    if seg.ident != "Result" {
+        return None;
+    }
+    let t = match &seg.arguments {
+        PathArguments::AngleBracketed(a) if matches!(a.args.len(), 1 | 2) => {
+            match a.args.first()? {
+                GenericArgument::Type(t) => t,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(match t {
+        Type::Tuple(t) if t.elems.is_empty() => ReturnType::Default,
+        t => ReturnType::Type(*arrow, Box::new(t.clone())),
+    })
+}
+
+/// Unwraps `ret`, reporting the error to host.
+///
+/// On error, `on_error` is used as the value of `ret` if set, otherwise `fallback` is evaluated.
+pub(crate) fn result_err_cvt(on_error: Option<&Expr>, fallback: TokenStream) -> TokenStream {
+    let on_error = match on_error {
+        Some(v) => quote!(#v),
+        None => fallback,
+    };
+
+    quote!(
+        let ret = match ret {
+            Ok(v) => v,
+            Err(e) => {
+                report_error(&e);
+                #on_error
+            }
+        };
+    )
+}
+
+/// Whether returned tuple is split into separate results.
+///
+/// Tuple is split, unless `returns` names a single result, which is then one array.
+/// Native values cannot be put in an array, so they are always split.
+pub(crate) fn split_results(metadata: &BindgenMetadata) -> bool {
+    metadata.use_native_types || !matches!(&metadata.returns, Some(v) if v.len() == 1)
+}
+
+pub(crate) fn result_count(output: &ReturnType, split: bool) -> usize {
+    match output {
+        ReturnType::Default => 0,
+        ReturnType::Type(_, t) => match &**t {
+            Type::Tuple(t) if split => t.elems.len(),
+            _ => 1,
+        },
+    }
+}
+
+/// Output type of a shim where every result is a `GodotValue`.
+pub(crate) fn variant_output(output: &ReturnType, split: bool) -> TokenStream {
+    match output {
+        v @ ReturnType::Default => quote!(#v),
+        ReturnType::Type(arrow, t) => match &**t {
+            Type::Tuple(t) if split => {
+                let r = iter::repeat_n(Ident::new("GodotValue", Span::call_site()), t.elems.len());
+                quote!(#arrow ( #(#r ,)* ))
+            }
+            _ => quote!(#arrow GodotValue),
+        },
+    }
+}
+
+/// Null value of the output of [`variant_output`].
+pub(crate) fn variant_null(output: &ReturnType, split: bool) -> TokenStream {
+    match output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, t) => match &**t {
+            Type::Tuple(t) if split => {
+                let r = iter::repeat_n(quote!(GodotValue::null()), t.elems.len());
+                quote!( ( #(#r ,)* ) )
+            }
+            _ => quote!(GodotValue::null()),
+        },
+    }
+}
+
+/// Converts `GodotValue` parameter into it's actual type.
+///
+/// The conversion goes through the trait of [`param_check`].
+pub(crate) fn variant_param_cvt(ty: &Type, i: usize) -> TokenStream {
+    let ident = format_ident!("arg{}", i);
+    let check = format_ident!("CheckParam{}", i, span = ty.span());
+    quote_spanned!(ty.span()=> #check::from_param(#ident))
+}
+
+/// Generates trait checking the type of exported parameter.
+///
+/// Unsupported type is then reported with the parameter name, instead of
+/// a generic error from somewhere in the shim.
+pub(crate) fn param_check(pat: &Pat, i: usize, native: bool) -> TokenStream {
+    let check = format_ident!("CheckParam{}", i);
+    let name = param_name(pat, i);
+    let message = format!("parameter `{}` has unsupported type `{{Self}}`", name);
+
+    if native {
+        quote!(
+            #[diagnostic::on_unimplemented(
+                message = #message,
+                label = "cannot be passed natively",
+                note = "supported native types are integers, `f32`, `f64`, `GodotValue`, `String`, `&str`, and `Vec<T>` or `&[T]` of `u8`, `u32`, `f32`, `Vector2`, `Vector3` or `Color`",
+                note = "use `#[variant]` to pass it as Godot value instead"
+            )]
+            trait #check {
+                const DATA_TYPE: u8;
+            }
+
+            impl<T: NativeType + ?Sized> #check for T {
+                const DATA_TYPE: u8 = T::DATA_TYPE;
+            }
         )
-        .to_tokens(tokens);
+    } else {
+        let name = format!("parameter {}", name);
+        quote!(
+            #[diagnostic::on_unimplemented(
+                message = #message,
+                label = "cannot be converted from Godot value",
+                note = "supported types are primitives, strings, Godot values, arrays and dictionaries, `Option<T>` and tuples of them",
+                note = "use `#[derive(GodotConvert)]` to convert your own type"
+            )]
+            trait #check: Sized {
+                const TYPE: TypeInfo;
+
+                fn from_param(value: GodotValue) -> Self;
+            }
+
+            impl<T: FromGodot> #check for T {
+                const TYPE: TypeInfo = <T as FromGodot>::TYPE;
+
+                fn from_param(value: GodotValue) -> Self {
+                    from_godot(value, #name)
+                }
+            }
+        )
+    }
+}
+
+/// Data type of exported parameter, checked by the trait of [`param_check`].
+pub(crate) fn param_data(ty: &Type, i: usize, native: bool) -> TokenStream {
+    let check = format_ident!("CheckParam{}", i, span = ty.span());
+    let ty = &static_lifetimes(ty);
+    if native {
+        quote_spanned!(ty.span()=> godot_wasm_bindgen::__hidden::ArgInfo::Native(<#ty as #check>::DATA_TYPE))
+    } else {
+        quote_spanned!(ty.span()=> godot_wasm_bindgen::__hidden::ArgInfo::Value(&<#ty as #check>::TYPE))
+    }
+}
+
+/// Converts `ret` into the output of [`variant_output`].
+pub(crate) fn variant_result_cvt(output: &ReturnType, split: bool) -> TokenStream {
+    match output {
+        ReturnType::Default => quote!(ret),
+        ReturnType::Type(_, t) => match &**t {
+            Type::Tuple(t) if split => {
+                let r = (0..t.elems.len()).map(Index::from);
+                quote!( ( #(IntoGodot::into_godot(ret.#r) ,)* ) )
+            }
+            _ => quote!(IntoGodot::into_godot(ret)),
+        },
+    }
+}
+
+/// Data types of results, where every result is a `GodotValue`.
+///
+/// Exported results are converted into value, while imported results are converted from it.
+pub(crate) fn variant_data(output: &ReturnType, is_import: bool, split: bool) -> Vec<TokenStream> {
+    match output {
+        ReturnType::Default => Vec::new(),
+        ReturnType::Type(_, v) => match &**v {
+            Type::Tuple(v) if split => v.elems.iter().map(|v| value_data(v, !is_import)).collect(),
+            v => vec![value_data(v, !is_import)],
+        },
+    }
+}
+
+pub(crate) fn native_data(ty: &Type) -> TokenStream {
+    let ty = &static_lifetimes(ty);
+    quote_spanned!(ty.span()=> godot_wasm_bindgen::__hidden::ArgInfo::Native(<#ty as NativeType>::DATA_TYPE))
+}
+
+/// Data type of `GodotValue` converted into or from `ty`.
+pub(crate) fn value_data(ty: &Type, into: bool) -> TokenStream {
+    let tr = if into {
+        quote!(IntoGodot)
+    } else {
+        quote!(FromGodot)
+    };
+    let ty = &static_lifetimes(ty);
+    quote_spanned!(ty.span()=> godot_wasm_bindgen::__hidden::ArgInfo::Value(
+        &<#ty as godot_wasm_bindgen::__hidden::#tr>::TYPE
+    ))
+}
+
+pub(crate) fn native_output_data(output: &ReturnType) -> Vec<TokenStream> {
+    match output {
+        ReturnType::Default => Vec::new(),
+        ReturnType::Type(_, v) => match &**v {
+            Type::Tuple(v) => v.elems.iter().map(native_data).collect(),
+            v => vec![native_data(v)],
+        },
+    }
+}
+
+pub(crate) fn write_name(bytes: &mut Vec<u8>, name: &str) {
+    leb128::write::unsigned(bytes, name.len() as _).unwrap();
+    bytes.extend(name.as_bytes());
+}
+
+/// Generates the static holding a symbol without arguments.
+pub(crate) fn raw_symbol_data(bytes: Vec<u8>) -> TokenStream {
+    parts_symbol_data(vec![bytes_part(bytes)])
+}
+
+/// Names and documentation of function symbol.
+pub(crate) struct SymbolInfo {
+    pub(crate) param_names: Vec<String>,
+    pub(crate) result_names: Vec<String>,
+    /// Number of trailing parameters with default value.
+    pub(crate) optional: usize,
+    pub(crate) doc: String,
+}
+
+impl SymbolInfo {
+    fn write(&self, bytes: &mut Vec<u8>) {
+        for names in [&self.param_names, &self.result_names] {
+            leb128::write::unsigned(bytes, names.len() as _).unwrap();
+            for n in names {
+                write_name(bytes, n);
+            }
+        }
+        leb128::write::unsigned(bytes, self.optional as _).unwrap();
+        write_name(bytes, &self.doc);
+    }
+}
+
+/// Generates the static holding a symbol.
+///
+/// `bytes` is the symbol header, each of `params` and `results` must evaluate to `ArgInfo`.
+pub(crate) fn symbol_data(
+    bytes: Vec<u8>,
+    params: Vec<TokenStream>,
+    results: Vec<TokenStream>,
+    info: &SymbolInfo,
+) -> TokenStream {
+    debug_assert_eq!(params.len(), info.param_names.len());
+    debug_assert_eq!(results.len(), info.result_names.len());
+    debug_assert!(info.optional <= params.len());
+
+    let mut parts = vec![bytes_part(bytes)];
+    for args in [params, results] {
+        let mut len = Vec::new();
+        leb128::write::unsigned(&mut len, args.len() as _).unwrap();
+        parts.push(bytes_part(len));
+        parts.extend(args.into_iter().map(arg_part));
+    }
+
+    let mut suffix = Vec::new();
+    info.write(&mut suffix);
+    parts.push(bytes_part(suffix));
+
+    parts_symbol_data(parts)
+}
+
+/// Generates the static holding a symbol, followed by `tail` and `suffix`.
+///
+/// Each of `tail` must evaluate to `ArgInfo`.
+pub(crate) fn typed_symbol_data(
+    bytes: Vec<u8>,
+    tail: Vec<TokenStream>,
+    suffix: Vec<u8>,
+) -> TokenStream {
+    let mut parts = vec![bytes_part(bytes)];
+    parts.extend(tail.into_iter().map(arg_part));
+    parts.push(bytes_part(suffix));
+
+    parts_symbol_data(parts)
+}
+
+fn bytes_part(bytes: Vec<u8>) -> TokenStream {
+    let bytes = bytes
+        .into_iter()
+        .map(|b| LitByte::new(b, Span::call_site()));
+    quote!(godot_wasm_bindgen::__hidden::SymbolPart::Bytes(&[#(#bytes),*]))
+}
+
+fn arg_part(arg: TokenStream) -> TokenStream {
+    quote!(godot_wasm_bindgen::__hidden::SymbolPart::Arg(#arg))
+}
+
+/// Generates the static holding a symbol assembled from `parts`.
+///
+/// Type descriptors have variable length, so the symbol is assembled in constant context.
+/// Version is prepended by the library, which defines the format.
+fn parts_symbol_data(parts: Vec<TokenStream>) -> TokenStream {
+    quote!(
+        const PARTS: &[godot_wasm_bindgen::__hidden::SymbolPart] = &[#(#parts),*];
+
+        #[link_section = "__godot_wasm_bindgen_data"]
+        #[doc(hidden)]
+        static DATA: [u8; godot_wasm_bindgen::__hidden::symbol_len(PARTS)] =
+            godot_wasm_bindgen::__hidden::symbol_data(PARTS);
+    )
+}
+
+/// Collects `///` doc comments.
+pub(crate) fn doc_string(attrs: &[Attribute]) -> String {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter(|a| a.path.is_ident("doc"))
+        .filter_map(|a| match a.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(v), ..
+            })) => Some(v.value()),
+            _ => None,
+        })
+        .collect();
+
+    lines
+        .iter()
+        .map(|v| v.strip_prefix(' ').unwrap_or(v))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Name of parameter, or it's position if it's a pattern.
+pub(crate) fn param_name(pat: &Pat, i: usize) -> String {
+    match pat {
+        Pat::Ident(v) => {
+            let s = v.ident.to_string();
+            match s.strip_prefix("r#") {
+                Some(v) => v.into(),
+                None => s,
+            }
+        }
+        _ => format!("arg{}", i),
+    }
+}
+
+/// Names of results, either from `returns` or by position.
+pub(crate) fn result_names(returns: Option<&Vec<LitStr>>, count: usize) -> Vec<String> {
+    match returns {
+        Some(v) => v.iter().map(|v| v.value()).collect(),
+        None if count == 1 => vec!["return".into()],
+        None => (0..count).map(|i| format!("return{}", i)).collect(),
+    }
+}
+
+/// Checks that `returns` names every result.
+pub(crate) fn check_returns(returns: Option<&Vec<LitStr>>, count: usize) -> ParseResult<()> {
+    match returns {
+        Some(v) if v.len() != count => Err(ParseError::new(
+            v.first().map_or_else(Span::call_site, |v| v.span()),
+            format!("Expected {} result names, got {}", count, v.len()),
+        )),
+        _ => Ok(()),
     }
 }
 
@@ -319,8 +1320,12 @@ pub struct ModuleName(String);
 
 impl Parse for ModuleName {
     fn parse(input: ParseStream) -> ParseResult<Self> {
+        let content;
+        parenthesized!(content in input);
+        let input = &content;
+
         let name: Ident = input.parse()?;
-        if name.to_string() != "wasm_import_module" {
+        if name != "wasm_import_module" {
             return Err(ParseError::new(name.span(), "Invalid link structure"));
         }
         input.parse::<Token![=]>()?;
@@ -334,6 +1339,7 @@ pub struct BindgenImport {
     item: ItemForeignMod,
 
     module_name: String,
+    native_params: Vec<Vec<bool>>,
 }
 
 impl BindgenImport {
@@ -353,17 +1359,62 @@ impl BindgenImport {
             }
         }
 
+        if let Some(v) = &metadata.name {
+            bail_syn!(v.span(), "Use #[link_name] to rename imported function");
+        }
+        if !metadata.prefix.is_empty() {
+            bail_syn!(item.span(), "Use #[link_name] to prefix imported function");
+        }
+        if metadata.no_constructor {
+            bail_syn!(item.span(), "Only exported struct can disable constructor");
+        }
+        if let Some(v) = metadata.returns.iter().flatten().next() {
+            bail_syn!(v.span(), "Result names cannot be set on import");
+        }
+        if metadata.start {
+            bail_syn!(item.span(), "Start function must be an exported function");
+        }
+        if let Some((t, _)) = metadata.instantiate.iter().flatten().next() {
+            bail_syn!(t.span(), "Only generic function can be instantiated");
+        }
+
         let mut errs = None;
-        for i in item.items.iter() {
-            if !matches!(i, ForeignItem::Fn(_)) {
-                join_errors::<()>(
-                    &mut errs,
-                    Err(ParseError::new_spanned(
-                        i,
-                        "Non-function item is currently unsupported",
-                    )),
-                );
+        let mut native_params = Vec::new();
+        for i in item.items.iter_mut() {
+            let f = match i {
+                ForeignItem::Fn(f) => f,
+                ForeignItem::Static(s) => {
+                    join_errors(&mut errs, check_static(s, metadata.use_native_types));
+                    continue;
+                }
+                i => {
+                    join_errors::<()>(
+                        &mut errs,
+                        Err(ParseError::new_spanned(
+                            i,
+                            "Only function and static items are supported",
+                        )),
+                    );
+                    continue;
+                }
+            };
+
+            if metadata.use_native_types {
+                join_errors(&mut errs, check_native_output(&f.sig.output, true));
+            }
+
+            let mut params = Vec::new();
+            for t in f.sig.inputs.iter_mut() {
+                if let FnArg::Typed(t) = t {
+                    if let Some(v) = join_errors(
+                        &mut errs,
+                        take_param_mode(&mut t.attrs, metadata.use_native_types),
+                    ) {
+                        params.push(v);
+                    }
+                }
             }
+            native_params.push(params);
         }
 
         if let Some(e) = errs {
@@ -380,122 +1431,233 @@ impl BindgenImport {
             metadata,
             item,
             module_name,
+            native_params,
+        })
+    }
+
+    fn functions(&self) -> impl Iterator<Item = (&ForeignItemFn, &[bool])> {
+        self.item
+            .items
+            .iter()
+            .filter_map(|v| match v {
+                ForeignItem::Fn(f) => Some(f),
+                _ => None,
+            })
+            .zip(self.native_params.iter().map(|v| &v[..]))
+    }
+
+    fn statics(&self) -> impl Iterator<Item = &ForeignItemStatic> {
+        self.item.items.iter().filter_map(|v| match v {
+            ForeignItem::Static(s) => Some(s),
+            _ => None,
         })
     }
+
+    /// Natively imported function without any conversion is imported as is.
+    fn has_shim(&self, f: &ForeignItemFn, native_params: &[bool]) -> bool {
+        !self.metadata.use_native_types
+            || native_params.contains(&false)
+            || f.sig.inputs.iter().any(|v| match v {
+                FnArg::Typed(v) => lowered_type(&v.ty).is_some() || is_borrowed_value(&v.ty),
+                FnArg::Receiver(_) => false,
+            })
+            || matches!(&f.sig.output, ReturnType::Type(_, t) if lowered_type(t).is_some())
+    }
 }
 
 impl ToTokens for BindgenImport {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        if self.metadata.use_native_types {
-            self.item.to_tokens(tokens);
-        } else {
-            let item = &self.item;
-            for i in item.items.iter().filter_map(|v| match v {
-                ForeignItem::Fn(f) => Some(f),
-                _ => None,
-            }) {
-                let func_ident = &i.sig.ident;
-                let ext_item = {
-                    let ItemForeignMod { attrs, abi, .. } = &*item;
-                    let ForeignItemFn {
-                        attrs: in_attrs,
-                        sig:
-                            Signature {
-                                fn_token,
-                                ident,
-                                inputs,
-                                output,
-                                ..
-                            },
-                        semi_token,
-                        ..
-                    } = &*i;
+        let native = self.metadata.use_native_types;
+        let item = &self.item;
 
-                    let inputs = inputs.iter().enumerate().map(|(i, _)| {
-                        let ident = format_ident!("arg{}", i);
-                        quote!(#ident : GodotValue)
-                    });
+        for (i, native_params) in self.functions() {
+            if !self.has_shim(i, native_params) {
+                continue;
+            }
 
-                    let output = match output {
-                        o @ ReturnType::Default => quote!(#o),
-                        ReturnType::Type(arrow, ty) => match &**ty {
-                            Type::Tuple(t) => {
-                                let v = iter::repeat(Ident::new("GodotValue", Span::call_site()))
-                                    .take(t.elems.len());
-                                quote!(#arrow (#(#v),*))
-                            }
-                            _ => quote!(#arrow GodotValue),
+            let func_ident = &i.sig.ident;
+            let ext_item = {
+                let ItemForeignMod { attrs, abi, .. } = item;
+                let ForeignItemFn {
+                    attrs: in_attrs,
+                    sig:
+                        Signature {
+                            fn_token,
+                            ident,
+                            inputs,
+                            output,
+                            ..
                         },
-                    };
+                    semi_token,
+                    ..
+                } = i;
 
-                    quote!(
-                        #(#attrs)*
-                        #abi {
-                            #(#in_attrs)*
-                            #fn_token #ident ( #(#inputs),* ) #output #semi_token
+                let inputs = inputs.iter().zip(native_params.iter()).enumerate().map(
+                    |(i, (v, &n))| match v {
+                        FnArg::Typed(v) if is_borrowed_value(&v.ty) => {
+                            let i = format_ident!("arg{}", i);
+                            quote!(#i : GodotValueRef<'_>)
                         }
-                    )
+                        FnArg::Typed(v) => param_arg(&v.ty, i, n),
+                        FnArg::Receiver(_) => unreachable!("Foreign function has no receiver"),
+                    },
+                );
+
+                let output = if native {
+                    native_output(output).0
+                } else {
+                    variant_output(output, true)
                 };
 
-                let ForeignItemFn { vis, sig, .. } = &*i;
-                let Signature {
-                    constness,
-                    asyncness,
-                    unsafety,
-                    abi,
-                    fn_token,
-                    output,
-                    ..
-                } = &*sig;
-
-                let param_args = sig.inputs.iter().enumerate().map(|(i, v)| match v {
-                    v @ FnArg::Receiver(_) => quote!(#v),
-                    FnArg::Typed(v) => {
-                        let ty = &*v.ty;
-                        let i = format_ident!("arg{}", i);
-                        quote!(#i : #ty)
+                quote!(
+                    #(#attrs)*
+                    #abi {
+                        #(#in_attrs)*
+                        #fn_token #ident ( #(#inputs),* ) #output #semi_token
                     }
-                });
+                )
+            };
 
-                let param_cvt = sig.inputs.iter().enumerate().map(|(i, v)| {
-                    let name = match v {
-                        FnArg::Receiver(v) => Ident::new("self", v.self_token.span),
-                        FnArg::Typed(_) => format_ident!("arg{}", i),
-                    };
-                    quote!(#name.into())
-                });
-
-                let ret_cvt = match &sig.output {
-                    ReturnType::Default => quote!(ret),
-                    ReturnType::Type(_, t) => match &**t {
-                        Type::Tuple(t) => {
-                            let r = (0..t.elems.len()).map(Index::from);
-                            quote!( ( #(ret.#r.into() ,)* ) )
+            let ForeignItemFn { vis, sig, .. } = i;
+            let Signature {
+                constness,
+                asyncness,
+                unsafety,
+                abi,
+                fn_token,
+                output,
+                ..
+            } = sig;
+
+            let param_args = sig.inputs.iter().enumerate().map(|(i, v)| match v {
+                v @ FnArg::Receiver(_) => quote!(#v),
+                FnArg::Typed(v) => {
+                    let ty = &*v.ty;
+                    let i = format_ident!("arg{}", i);
+                    quote!(#i : #ty)
+                }
+            });
+
+            let param_cvt =
+                sig.inputs
+                    .iter()
+                    .zip(native_params.iter())
+                    .enumerate()
+                    .map(|(i, (v, &n))| {
+                        let name = match v {
+                            FnArg::Receiver(v) => Ident::new("self", v.self_token.span),
+                            FnArg::Typed(_) => format_ident!("arg{}", i),
+                        };
+                        match v {
+                            FnArg::Typed(v) if n && lowered_type(&v.ty).is_some() => {
+                                quote!(#name.as_ptr() as _, #name.len() as _)
+                            }
+                            FnArg::Typed(v) if is_borrowed_value(&v.ty) => {
+                                quote!(GodotValueRef::new(#name))
+                            }
+                            _ if n => quote!(#name),
+                            _ => quote!(IntoGodot::into_godot(#name)),
                         }
-                        _ => quote!(ret.try_into().unwrap()),
-                    },
+                    });
+
+            let ret_cvt = import_result_cvt(&sig.output, native);
+
+            quote!(
+                #vis #constness #asyncness #unsafety #abi #fn_token #func_ident ( #(#param_args),* ) #output {
+                    use godot_wasm_bindgen::__hidden::{
+                        from_godot, raw_string, raw_vec, GodotValue, GodotValueRef, IntoGodot,
+                    };
+
+                    #ext_item
+
+                    unsafe {
+                        let ret = #func_ident ( #(#param_cvt),* );
+
+                        #ret_cvt
+                    }
+                }
+            )
+            .to_tokens(tokens);
+        }
+
+        for i in self.statics() {
+            let ForeignItemStatic {
+                attrs,
+                vis,
+                static_token,
+                ident,
+                ty,
+                ..
+            } = i;
+            let name = match link_name(attrs) {
+                Some(v) => v,
+                None => ident.to_string(),
+            };
+            let attrs = attrs.iter().filter(|a| !a.path.is_ident("link_name"));
+
+            let ext_item = {
+                let ItemForeignMod { attrs, abi, .. } = item;
+                let output = static_output(i);
+                let output = if native {
+                    native_output(&output).0
+                } else {
+                    variant_output(&output, true)
                 };
 
                 quote!(
-                    #vis #constness #asyncness #unsafety #abi #fn_token #func_ident ( #(#param_args),* ) #output {
-                        use godot_wasm_bindgen::__hidden::{DataTypeValue, GodotValue};
+                    #(#attrs)*
+                    #abi {
+                        #[link_name = #name]
+                        fn getter() #output;
+                    }
+                )
+            };
+
+            let ret_cvt = if native {
+                import_result_cvt(&static_output(i), true)
+            } else {
+                let n = format!("static {}", ident);
+                quote!(from_godot(ret, #n))
+            };
+
+            quote!(
+                #(#attrs)*
+                #vis #static_token #ident: godot_wasm_bindgen::host_static::HostStatic<#ty> = {
+                    fn fetch() -> #ty {
+                        use godot_wasm_bindgen::__hidden::{from_godot, raw_string, raw_vec, GodotValue};
 
                         #ext_item
 
                         unsafe {
-                            let ret = #func_ident ( #(#param_cvt),* );
+                            let ret = getter();
 
                             #ret_cvt
                         }
                     }
-                )
-                .to_tokens(tokens);
-            }
 
+                    thread_local! {
+                        static CACHE: std::cell::OnceCell<#ty> = const { std::cell::OnceCell::new() };
+                    }
+
+                    godot_wasm_bindgen::host_static::HostStatic::new(&CACHE, fetch)
+                };
+            )
+            .to_tokens(tokens);
+        }
+
+        {
             let ItemForeignMod {
                 attrs, abi, items, ..
             } = item;
-            let items = items.iter().filter(|v| !matches!(v, ForeignItem::Fn(_)));
+            let mut fns = self.functions();
+            let items = items.iter().filter(|v| match v {
+                ForeignItem::Fn(_) => match fns.next() {
+                    Some((f, native_params)) => !self.has_shim(f, native_params),
+                    None => unreachable!("Function count mismatch"),
+                },
+                ForeignItem::Static(_) => false,
+                _ => true,
+            });
 
             quote!(
                 #(#attrs)* #abi {
@@ -505,102 +1667,91 @@ impl ToTokens for BindgenImport {
             .to_tokens(tokens);
         }
 
-        for i in self.item.items.iter().filter_map(|v| match v {
-            ForeignItem::Fn(f) => Some(f),
-            _ => None,
-        }) {
-            let name = i.sig.ident.to_string();
+        for (i, native_params) in self.functions() {
+            let name = match link_name(&i.attrs) {
+                Some(v) => v,
+                None => i.sig.ident.to_string(),
+            };
 
             let mut bytes = Vec::new();
             leb128::write::unsigned(&mut bytes, 0).unwrap();
-            leb128::write::unsigned(&mut bytes, self.module_name.len() as _).unwrap();
-            bytes.extend(self.module_name.as_bytes());
-            leb128::write::unsigned(&mut bytes, name.len() as _).unwrap();
-            bytes.extend(name.as_bytes());
-
-            let param_count = i.sig.inputs.len();
-            let result_count = match &i.sig.output {
-                ReturnType::Default => 0,
-                ReturnType::Type(_, t) => match &**t {
-                    Type::Tuple(t) => t.elems.len(),
-                    _ => 1,
-                },
-            };
+            write_name(&mut bytes, &self.module_name);
+            write_name(&mut bytes, &name);
 
-            let (param_len, param_bcount) = {
-                let mut bytes = Vec::new();
-                leb128::write::unsigned(&mut bytes, param_count as _).unwrap();
-
-                let l = bytes.len();
-                let ret = bytes
-                    .into_iter()
-                    .map(|b| LitByte::new(b, Span::call_site()));
-                (ret, l)
+            let param_data = i
+                .sig
+                .inputs
+                .iter()
+                .zip(native_params.iter())
+                .map(|(v, &n)| match v {
+                    FnArg::Typed(v) if is_borrowed_value(&v.ty) => {
+                        native_data(&parse_quote!(GodotValueRef))
+                    }
+                    FnArg::Typed(v) => mode_data(&v.ty, n),
+                    FnArg::Receiver(_) => unreachable!("Foreign function has no receiver"),
+                })
+                .collect();
+            let result_data = if native {
+                native_output_data(&i.sig.output)
+            } else {
+                variant_data(&i.sig.output, true, true)
             };
-            let (result_len, result_bcount) = {
-                let mut bytes = Vec::new();
-                leb128::write::unsigned(&mut bytes, result_count as _).unwrap();
-
-                let l = bytes.len();
-                let ret = bytes
-                    .into_iter()
-                    .map(|b| LitByte::new(b, Span::call_site()));
-                (ret, l)
+
+            let info = SymbolInfo {
+                param_names: i
+                    .sig
+                    .inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| match v {
+                        FnArg::Typed(v) => param_name(&v.pat, i),
+                        FnArg::Receiver(_) => unreachable!("Foreign function has no receiver"),
+                    })
+                    .collect(),
+                result_names: result_names(None, result_count(&i.sig.output, true)),
+                optional: 0,
+                doc: doc_string(&i.attrs),
             };
 
-            let extra_count = param_bcount + result_bcount + param_count + result_count;
-            bytes.extend(iter::repeat(0).take(extra_count));
-            tag_length(&mut bytes);
-            bytes.truncate(bytes.len() - extra_count);
-            prepend(&mut bytes, &[1, 0, 0, 0]);
-
-            let bytes_len_token =
-                LitInt::new(&format!("{}", bytes.len() + extra_count), Span::call_site());
-            let bytes_token = bytes
-                .into_iter()
-                .map(|b| LitByte::new(b, Span::call_site()));
-
-            let param_data = if !self.metadata.use_native_types {
-                let v = iter::repeat(Ident::new("GodotValue", Span::call_site())).take(param_count);
-                quote!(#(<DataTypeValue<#v>>::value() ,)*)
-            } else {
-                let v = i.sig.inputs.iter().map(|v| match &*v {
-                    FnArg::Typed(v) => &*v.ty,
-                    FnArg::Receiver(_) => unreachable!("Method function should get filtered"),
-                });
-                quote!(#(<DataTypeValue<#v>>::value() ,)*)
+            let data = symbol_data(bytes, param_data, result_data, &info);
+
+            quote!(
+                const _: () = {
+                    use godot_wasm_bindgen::__hidden::{NativeType, GodotValue, GodotValueRef};
+
+                    #data
+                };
+            )
+            .to_tokens(tokens);
+        }
+
+        for i in self.statics() {
+            let name = match link_name(&i.attrs) {
+                Some(v) => v,
+                None => i.ident.to_string(),
             };
 
-            let result_data = if !self.metadata.use_native_types {
-                let v =
-                    iter::repeat(Ident::new("GodotValue", Span::call_site())).take(result_count);
-                quote!(#(<DataTypeValue<#v>>::value() ,)*)
+            let mut bytes = Vec::new();
+            leb128::write::unsigned(&mut bytes, 1).unwrap();
+            write_name(&mut bytes, &self.module_name);
+            write_name(&mut bytes, &name);
+
+            let ty_data = if native {
+                native_data(&i.ty)
             } else {
-                match &i.sig.output {
-                    ReturnType::Default => quote!(),
-                    ReturnType::Type(_, v) => match &**v {
-                        Type::Tuple(v) => {
-                            let v = v.elems.iter();
-                            quote!(#(<DataTypeValue<#v>>::value() ,)*)
-                        }
-                        v => quote!(<DataTypeValue<#v>>::value(),),
-                    },
-                }
+                value_data(&i.ty, false)
             };
 
+            let mut doc = Vec::new();
+            write_name(&mut doc, &doc_string(&i.attrs));
+
+            let data = typed_symbol_data(bytes, vec![ty_data], doc);
+
             quote!(
                 const _: () = {
-                    use godot_wasm_bindgen::__hidden::{DataTypeValue, GodotValue};
-
-                    #[link_section = "__godot_wasm_bindgen_data"]
-                    #[doc(hidden)]
-                    static DATA: [u8; #bytes_len_token] = [
-                        #(#bytes_token ,)*
-                        #(#param_len ,)*
-                        #param_data
-                        #(#result_len ,)*
-                        #result_data
-                    ];
+                    use godot_wasm_bindgen::__hidden::{NativeType, GodotValue};
+
+                    #data
                 };
             )
             .to_tokens(tokens);
@@ -608,16 +1759,29 @@ impl ToTokens for BindgenImport {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum BindgenInput {
     Function(BindgenFunction),
     Import(BindgenImport),
+    Impl(BindgenImpl),
+    Struct(BindgenStruct),
+    Const(BindgenConst),
+    Module(BindgenModule),
+    Generic(BindgenGeneric),
 }
 
 impl BindgenInput {
     pub fn process(metadata: BindgenMetadata, input: TokenStream) -> ParseResult<Self> {
         match syn::parse2(input)? {
+            Item::Fn(f) if has_type_param(&f.sig.generics) => {
+                Ok(Self::Generic(BindgenGeneric::new(metadata, f)?))
+            }
             Item::Fn(f) => Ok(Self::Function(BindgenFunction::new(metadata, f)?)),
             Item::ForeignMod(i) => Ok(Self::Import(BindgenImport::new(metadata, i)?)),
+            Item::Impl(i) => Ok(Self::Impl(BindgenImpl::new(metadata, i)?)),
+            Item::Struct(s) => Ok(Self::Struct(BindgenStruct::new(metadata, s)?)),
+            Item::Const(c) => Ok(Self::Const(BindgenConst::new(metadata, c)?)),
+            Item::Mod(m) => Ok(Self::Module(BindgenModule::new(metadata, m)?)),
             item => bail_syn!(item.span(), "Unknown or unsupported item type"),
         }
     }
@@ -628,6 +1792,11 @@ impl ToTokens for BindgenInput {
         match self {
             Self::Function(f) => f.to_tokens(tokens),
             Self::Import(i) => i.to_tokens(tokens),
+            Self::Impl(i) => i.to_tokens(tokens),
+            Self::Struct(s) => s.to_tokens(tokens),
+            Self::Const(c) => c.to_tokens(tokens),
+            Self::Module(m) => m.to_tokens(tokens),
+            Self::Generic(g) => g.to_tokens(tokens),
         }
     }
 }
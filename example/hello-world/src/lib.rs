@@ -1,6 +1,240 @@
+use std::cell::RefCell;
+
 use godot_wasm_bindgen::prelude::*;
 
+thread_local! {
+    static SQUARES: RefCell<Vec<i64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Builds the lookup table once the module is instantiated.
+#[godot_wasm_bindgen(start)]
+pub fn init() {
+    SQUARES.with(|v| *v.borrow_mut() = (0..16).map(|i| i * i).collect());
+}
+
+#[godot_wasm_bindgen]
+pub fn square(i: i64) -> Option<i64> {
+    SQUARES.with(|v| v.borrow().get(usize::try_from(i).ok()?).copied())
+}
+
+/// Maximum health of a player.
+#[godot_wasm_bindgen]
+pub const MAX_HP: i64 = 100;
+
+#[godot_wasm_bindgen(name = "SpawnPoint")]
+pub const SPAWN_POINT: (f64, f64) = (4.0, 2.5);
+
+#[godot_wasm_bindgen(use_native_types)]
+pub const VERSION: &str = "1.0";
+
+/// Adds two numbers.
 #[godot_wasm_bindgen]
 pub fn adder(a: i32, b: i32) -> i32 {
     a + b
 }
+
+#[godot_wasm_bindgen(returns = ("quotient", "remainder"))]
+pub fn divmod(a: i64, b: i64) -> (i64, i64) {
+    (a.div_euclid(b), a.rem_euclid(b))
+}
+
+/// Simple counter object.
+#[godot_wasm_bindgen]
+#[derive(Default)]
+pub struct Counter {
+    count: i64,
+}
+
+#[godot_wasm_bindgen]
+impl Counter {
+    pub fn create(start: i64) -> Self {
+        Self { count: start }
+    }
+
+    /// Increments the counter by `by`.
+    pub fn increment(&mut self, #[native] by: i64) {
+        self.count += by;
+    }
+
+    pub fn reset(&mut self, #[default = 0] to: i64) {
+        self.count = to;
+    }
+
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+}
+
+#[godot_wasm_bindgen(use_native_types)]
+#[no_mangle]
+pub extern "C" fn native_adder(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[godot_wasm_bindgen]
+pub fn scale(v: Vector2, #[native] factor: f32) -> Vector2 {
+    v * factor
+}
+
+#[godot_wasm_bindgen]
+pub fn checked_div(a: i64, b: i64) -> Result<i64, String> {
+    match a.checked_div(b) {
+        Some(v) => Ok(v),
+        None => Err(format!("Cannot divide {} by {}", a, b)),
+    }
+}
+
+#[godot_wasm_bindgen]
+pub fn normalized(v: Option<Vector2>) -> Option<Vector2> {
+    v.filter(|v| v.x != 0.0 || v.y != 0.0)
+        .map(|v| v / (v.x * v.x + v.y * v.y).sqrt())
+}
+
+#[godot_wasm_bindgen(on_error = -1)]
+pub fn parse_int(s: String) -> Result<i64, std::num::ParseIntError> {
+    s.parse()
+}
+
+/// Linearly interpolates between `from` and `to`, halfway by default.
+#[godot_wasm_bindgen]
+pub fn lerp(from: f64, to: f64, #[default = 0.5] weight: f64) -> f64 {
+    from + (to - from) * weight
+}
+
+/// Returns the larger of `a` and `b`.
+#[godot_wasm_bindgen(instantiate(i64 => "max_int", f64 => "max_float", String => "max_string"))]
+pub fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if a < b {
+        b
+    } else {
+        a
+    }
+}
+
+/// Sums `0..n`, yielding to the host every thousand numbers.
+#[godot_wasm_bindgen]
+pub async fn slow_sum(n: i64) -> i64 {
+    let mut sum = 0;
+    for i in 0..n {
+        sum += i;
+        if i % 1000 == 999 {
+            godot_wasm_bindgen::task::yield_now().await;
+        }
+    }
+    sum
+}
+
+#[godot_wasm_bindgen(name = "VectorLength")]
+pub fn vector_length(v: Vector2) -> f32 {
+    (v.x * v.x + v.y * v.y).sqrt()
+}
+
+#[godot_wasm_bindgen(prefix = "combat_")]
+pub mod combat {
+    use godot_wasm_bindgen::prelude::*;
+
+    #[godot_wasm_bindgen]
+    #[derive(Default)]
+    pub struct Fighter {
+        health: i64,
+    }
+
+    #[godot_wasm_bindgen]
+    impl Fighter {
+        pub fn hit(&mut self, damage: i64) -> bool {
+            self.health -= damage;
+            self.health <= 0
+        }
+    }
+
+    #[godot_wasm_bindgen(name = "OnPlayerHit")]
+    pub fn on_player_hit(damage: i64) -> i64 {
+        damage * 2
+    }
+}
+
+#[godot_wasm_bindgen(use_native_types)]
+pub fn greet(name: &str) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[godot_wasm_bindgen(use_native_types)]
+pub fn sum_bytes(data: &[u8]) -> u32 {
+    data.iter().map(|&v| v as u32).sum()
+}
+
+#[godot_wasm_bindgen]
+extern "C" {
+    /// Health of newly spawned player, provided by host.
+    static START_HEALTH: i64;
+}
+
+#[godot_wasm_bindgen]
+pub fn start_health() -> i64 {
+    START_HEALTH.get()
+}
+
+#[derive(GodotConvert)]
+pub struct SaveData {
+    pub level: i64,
+    #[godot(rename = "player_name")]
+    pub name: String,
+    #[godot(default)]
+    pub position: Option<Vector2>,
+}
+
+#[godot_wasm_bindgen]
+pub fn next_level(data: SaveData) -> SaveData {
+    SaveData {
+        level: data.level + 1,
+        ..data
+    }
+}
+
+#[derive(GodotConvert, Clone, Copy)]
+pub enum Difficulty {
+    Easy = 1,
+    Normal,
+    Hard = 5,
+}
+
+#[derive(GodotConvert)]
+#[godot(repr = "string")]
+pub enum Weapon {
+    Sword,
+    #[godot(rename = "bow")]
+    Bow,
+}
+
+#[godot_wasm_bindgen]
+pub fn weapon_damage(weapon: Weapon, difficulty: Difficulty) -> i64 {
+    let base = match weapon {
+        Weapon::Sword => 10,
+        Weapon::Bow => 6,
+    };
+    base * difficulty as i64
+}
+
+#[derive(GodotConvert)]
+pub struct Meters(pub f64);
+
+#[derive(GodotConvert)]
+pub struct Motion(pub Vector2, pub Vector2);
+
+#[godot_wasm_bindgen]
+pub fn step(motion: Motion, delta: Meters) -> Motion {
+    let Motion(pos, vel) = motion;
+    Motion(pos + vel * delta.0 as f32, vel)
+}
+
+#[godot_wasm_bindgen]
+pub fn swap(pair: Option<(i64, String)>) -> Option<(String, i64)> {
+    pair.map(|(a, b)| (b, a))
+}
+
+/// Returns `[pos, vel]` after falling for `delta` seconds.
+#[godot_wasm_bindgen(returns = "motion")]
+pub fn fall(pos: Vector2, vel: Vector2, delta: f32) -> (Vector2, Vector2) {
+    let vel = vel + Vector2 { x: 0.0, y: 9.8 } * delta;
+    (pos + vel * delta, vel)
+}
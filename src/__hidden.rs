@@ -1,8 +1,20 @@
 #![doc(hidden)]
 
+use std::alloc::{alloc as alloc_, dealloc, Layout};
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt::Display;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
+pub use crate::class::{GodotClass, Registry};
+pub use crate::convert::{
+    array_check_len, array_get, ConvertError, FromGodot, IntoGodot, TypeInfo,
+};
+pub use crate::godot_array::GodotArray;
+pub use crate::godot_dictionary::GodotDictionary;
 pub use crate::godot_value::GodotValue;
+pub use crate::task::spawn as spawn_task;
 
 #[repr(u8)]
 enum DataTypeEnum {
@@ -17,72 +29,412 @@ enum DataTypeEnum {
     F32,
     F64,
     GodotValue,
+    Handle,
+    GodotValueRef,
+    String,
+    ByteArray,
+    IntArray,
+    FloatArray,
+    Vector2Array,
+    Vector3Array,
+    ColorArray,
 }
 
-pub struct DataTypeValue<T>(PhantomData<T>);
+/// Type passed natively, without converting into Godot value.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be passed natively",
+    label = "unsupported native type",
+    note = "supported native types are integers, `f32`, `f64`, `GodotValue`, `String`, `&str`, and `Vec<T>` or `&[T]` of `u8`, `u32`, `f32`, `Vector2`, `Vector3` or `Color`",
+    note = "use `#[variant]` to pass it as Godot value instead"
+)]
+pub trait NativeType {
+    /// Type of the argument in metadata.
+    const DATA_TYPE: u8;
+}
 
-impl DataTypeValue<u8> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::U8 as _
-    }
+/// Argument of symbol in metadata.
+pub enum ArgInfo {
+    /// Argument passed natively, of [`NativeType::DATA_TYPE`].
+    Native(u8),
+    /// Argument passed as Godot value of the type.
+    Value(&'static TypeInfo),
 }
 
-impl DataTypeValue<i8> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::I8 as _
-    }
+/// Part of symbol in metadata.
+pub enum SymbolPart {
+    Bytes(&'static [u8]),
+    Arg(ArgInfo),
 }
 
-impl DataTypeValue<u16> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::U16 as _
-    }
+/// Writes symbol in constant context.
+///
+/// Bytes past `N` are only counted, so `SymbolWriter<0>` computes the length.
+struct SymbolWriter<const N: usize> {
+    buf: [u8; N],
+    len: usize,
 }
 
-impl DataTypeValue<i16> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::I16 as _
+impl<const N: usize> SymbolWriter<N> {
+    const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    const fn byte(mut self, v: u8) -> Self {
+        if self.len < N {
+            self.buf[self.len] = v;
+        }
+        self.len += 1;
+        self
+    }
+
+    const fn bytes(mut self, v: &[u8]) -> Self {
+        let mut i = 0;
+        while i < v.len() {
+            self = self.byte(v[i]);
+            i += 1;
+        }
+        self
+    }
+
+    const fn leb128(mut self, mut v: usize) -> Self {
+        while v >= 128 {
+            self = self.byte((v & 127) as u8 | 128);
+            v >>= 7;
+        }
+        self.byte(v as u8)
+    }
+
+    const fn str(self, v: &str) -> Self {
+        self.leb128(v.len()).bytes(v.as_bytes())
+    }
+
+    /// Writes type descriptor, which is a tag followed by it's contents:
+    /// - `0`: Any value.
+    /// - `1`: Value of the [`ValueType`](crate::godot_value::ValueType) byte.
+    /// - `2`: Optional value of the inner type.
+    /// - `3`: Array of the inner type.
+    /// - `4`: Tuple, element count followed by element types.
+    /// - `5`: Struct, field count followed by field names and types.
+    /// - `6`: Pool array of the [`PoolElement`](crate::convert::PoolElement) byte.
+    const fn type_info(mut self, v: &TypeInfo) -> Self {
+        match v {
+            TypeInfo::Any => self.byte(0),
+            TypeInfo::Value(v) => self.byte(1).byte(*v as u8),
+            TypeInfo::Optional(v) => self.byte(2).type_info(v),
+            TypeInfo::Array(v) => self.byte(3).type_info(v),
+            TypeInfo::Tuple(v) => {
+                self = self.byte(4).leb128(v.len());
+                let mut i = 0;
+                while i < v.len() {
+                    self = self.type_info(&v[i]);
+                    i += 1;
+                }
+                self
+            }
+            TypeInfo::Struct(v) => {
+                self = self.byte(5).leb128(v.len());
+                let mut i = 0;
+                while i < v.len() {
+                    self = self.str(v[i].0).type_info(&v[i].1);
+                    i += 1;
+                }
+                self
+            }
+            TypeInfo::PoolArray(v) => self.byte(6).byte(*v as u8),
+        }
+    }
+
+    /// Godot value argument is followed by it's type descriptor.
+    const fn arg(self, v: &ArgInfo) -> Self {
+        const GODOT_VALUE: u8 = DataTypeEnum::GodotValue as _;
+        match v {
+            ArgInfo::Native(GODOT_VALUE) => self.byte(GODOT_VALUE).type_info(&TypeInfo::Any),
+            ArgInfo::Native(v) => self.byte(*v),
+            ArgInfo::Value(v) => self.byte(GODOT_VALUE).type_info(v),
+        }
+    }
+
+    const fn parts(mut self, v: &[SymbolPart]) -> Self {
+        let mut i = 0;
+        while i < v.len() {
+            self = match &v[i] {
+                SymbolPart::Bytes(v) => self.bytes(v),
+                SymbolPart::Arg(v) => self.arg(v),
+            };
+            i += 1;
+        }
+        self
     }
 }
 
-impl DataTypeValue<u32> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::U32 as _
+/// Version of the metadata format, as little-endian major and minor `u16`.
+///
+/// Minor version may only add symbol kinds, major version is incompatible.
+/// Must match `Version::CURRENT` of the CLI.
+const SYMBOL_VERSION: [u8; 4] = [2, 0, 0, 0];
+
+/// Length of symbol assembled by [`symbol_data`].
+pub const fn symbol_len(parts: &[SymbolPart]) -> usize {
+    let len = SymbolWriter::<0>::new().parts(parts).len;
+    SymbolWriter::<0>::new()
+        .bytes(&SYMBOL_VERSION)
+        .leb128(len)
+        .len
+        + len
+}
+
+/// Assembles symbol from `parts`, prepending version and length.
+pub const fn symbol_data<const N: usize>(parts: &[SymbolPart]) -> [u8; N] {
+    let len = SymbolWriter::<0>::new().parts(parts).len;
+    let ret = SymbolWriter::<N>::new()
+        .bytes(&SYMBOL_VERSION)
+        .leb128(len)
+        .parts(parts);
+    assert!(ret.len == N, "Symbol length mismatch");
+    ret.buf
+}
+
+/// Converts value, panicking with the name of the converted item on failure.
+pub fn from_godot<T: FromGodot>(v: GodotValue, name: &str) -> T {
+    match T::from_godot(v) {
+        Ok(v) => v,
+        Err(e) => panic!("Cannot convert {}: {}", name, e),
     }
 }
 
-impl DataTypeValue<i32> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::I32 as _
+/// Gets and converts value of dictionary key.
+///
+/// If `default` is set, missing key is defaulted instead.
+pub fn dict_get<T: FromGodot>(
+    dict: &GodotDictionary,
+    key: &'static str,
+    default: Option<fn() -> T>,
+) -> Result<T, ConvertError>
+where
+    T::Error: Error + 'static,
+{
+    match dict.get(&key.into_godot()) {
+        Some(v) => T::from_godot(v).map_err(|e| ConvertError::field(key, e)),
+        None => match default {
+            Some(f) => Ok(f()),
+            None => Err(ConvertError::MissingKey(key)),
+        },
     }
 }
 
-impl DataTypeValue<u64> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::U64 as _
+pub fn dict_set<T: IntoGodot>(dict: &mut GodotDictionary, key: &str, v: T) {
+    dict.insert(&key.into_godot(), &v.into_godot());
+}
+
+#[link(wasm_import_module = "godot_wasm")]
+extern "C" {
+    #[link_name = "error"]
+    fn report_error_(ptr: *const u8, len: u32);
+}
+
+/// Sends error message to host.
+pub fn report_error(e: &dyn Display) {
+    let s = e.to_string();
+    unsafe { report_error_(s.as_ptr(), s.len() as _) }
+}
+
+/// Borrowed value passed to imported function.
+///
+/// Unlike [`GodotValue`], the handle is not freed by the host.
+#[repr(transparent)]
+pub struct GodotValueRef<'a>(u32, PhantomData<&'a GodotValue>);
+
+impl<'a> GodotValueRef<'a> {
+    pub fn new<T: AsRef<GodotValue> + ?Sized>(v: &'a T) -> Self {
+        Self(unsafe { v.as_ref().to_raw() }, PhantomData)
     }
 }
 
-impl DataTypeValue<i64> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::I64 as _
+/// Allocates buffer of array passed from host.
+#[export_name = "__godot_wasm_bindgen_alloc"]
+pub extern "C" fn alloc(size: u32, align: u32) -> *mut u8 {
+    if size == 0 {
+        // Dangling but aligned, like empty Vec
+        return align as _;
     }
+
+    let layout = match Layout::from_size_align(size as _, align as _) {
+        Ok(v) => v,
+        Err(e) => panic!("Invalid layout: {}", e),
+    };
+    unsafe { alloc_(layout) }
 }
 
-impl DataTypeValue<f32> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::F32 as _
+/// Frees buffer of array passed to host.
+///
+/// # Safety
+///
+/// Buffer must be allocated with the same size and alignment.
+#[export_name = "__godot_wasm_bindgen_free"]
+pub unsafe extern "C" fn free(ptr: *mut u8, size: u32, align: u32) {
+    if size != 0 {
+        dealloc(
+            ptr,
+            Layout::from_size_align_unchecked(size as _, align as _),
+        );
     }
 }
 
-impl DataTypeValue<f64> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::F64 as _
+/// Takes ownership of array allocated with [`alloc`].
+///
+/// # Safety
+///
+/// Buffer must be allocated by [`alloc`] with the layout of `[T; len]` and initialized.
+pub unsafe fn raw_vec<T>(ptr: *mut T, len: u32) -> Vec<T> {
+    Vec::from_raw_parts(ptr, len as _, len as _)
+}
+
+/// Takes ownership of string allocated with [`alloc`].
+///
+/// Invalid UTF-8 is reported to host with [`report_error`] and replaced, instead of trapping.
+///
+/// # Safety
+///
+/// See [`raw_vec`].
+pub unsafe fn raw_string(ptr: *mut u8, len: u32) -> String {
+    match String::from_utf8(raw_vec(ptr, len)) {
+        Ok(v) => v,
+        Err(e) => {
+            report_error(&format_args!("Invalid string: {}", e));
+            String::from_utf8_lossy(e.as_bytes()).into_owned()
+        }
     }
 }
 
-impl DataTypeValue<GodotValue> {
-    pub const fn value() -> u8 {
-        DataTypeEnum::GodotValue as _
+/// Gives away ownership of array, to be freed with [`free`].
+pub fn into_raw_vec<T>(v: Vec<T>) -> (*mut T, u32) {
+    let v = Box::into_raw(v.into_boxed_slice());
+    (v as _, v.len() as _)
+}
+
+pub fn into_raw_string(v: String) -> (*mut u8, u32) {
+    into_raw_vec(v.into_bytes())
+}
+
+/// Opaque handle to an exported Rust object.
+///
+/// The handle is a key into the [`Registry`](crate::class::Registry) of exported objects,
+/// which checks the type of the object.
+#[repr(transparent)]
+pub struct Handle(u32);
+
+impl Handle {
+    pub fn new<T: GodotClass>(v: T) -> Self {
+        Self(v.into_handle())
+    }
+
+    /// Creates null handle, which never refers to an object.
+    pub const fn null() -> Self {
+        Self(0)
+    }
+
+    pub fn get<T: GodotClass>(&self) -> Rc<RefCell<T>> {
+        match T::from_handle(self.0) {
+            Some(v) => v,
+            None => panic!(
+                "Invalid handle {} of {}",
+                self.0,
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+
+    pub fn delete<T: GodotClass>(self) {
+        if T::remove_handle(self.0).is_none() {
+            panic!(
+                "Invalid handle {} of {}",
+                self.0,
+                std::any::type_name::<T>()
+            );
+        }
     }
 }
+
+impl NativeType for u8 {
+    const DATA_TYPE: u8 = DataTypeEnum::U8 as _;
+}
+
+impl NativeType for i8 {
+    const DATA_TYPE: u8 = DataTypeEnum::I8 as _;
+}
+
+impl NativeType for u16 {
+    const DATA_TYPE: u8 = DataTypeEnum::U16 as _;
+}
+
+impl NativeType for i16 {
+    const DATA_TYPE: u8 = DataTypeEnum::I16 as _;
+}
+
+impl NativeType for u32 {
+    const DATA_TYPE: u8 = DataTypeEnum::U32 as _;
+}
+
+impl NativeType for i32 {
+    const DATA_TYPE: u8 = DataTypeEnum::I32 as _;
+}
+
+impl NativeType for u64 {
+    const DATA_TYPE: u8 = DataTypeEnum::U64 as _;
+}
+
+impl NativeType for i64 {
+    const DATA_TYPE: u8 = DataTypeEnum::I64 as _;
+}
+
+impl NativeType for f32 {
+    const DATA_TYPE: u8 = DataTypeEnum::F32 as _;
+}
+
+impl NativeType for f64 {
+    const DATA_TYPE: u8 = DataTypeEnum::F64 as _;
+}
+
+impl NativeType for GodotValue {
+    const DATA_TYPE: u8 = DataTypeEnum::GodotValue as _;
+}
+
+impl NativeType for Handle {
+    const DATA_TYPE: u8 = DataTypeEnum::Handle as _;
+}
+
+impl NativeType for GodotValueRef<'_> {
+    const DATA_TYPE: u8 = DataTypeEnum::GodotValueRef as _;
+}
+
+impl NativeType for &'_ str {
+    const DATA_TYPE: u8 = DataTypeEnum::String as _;
+}
+
+impl NativeType for String {
+    const DATA_TYPE: u8 = DataTypeEnum::String as _;
+}
+
+macro_rules! pool_data_type {
+    ($($t:ty => $v:ident),* $(,)?) => {$(
+        impl NativeType for &'_ [$t] {
+            const DATA_TYPE: u8 = DataTypeEnum::$v as _;
+        }
+
+        impl NativeType for Vec<$t> {
+            const DATA_TYPE: u8 = DataTypeEnum::$v as _;
+        }
+    )*};
+}
+
+pool_data_type!(
+    u8 => ByteArray,
+    u32 => IntArray,
+    f32 => FloatArray,
+    crate::primitive::Vector2 => Vector2Array,
+    crate::primitive::Vector3 => Vector3Array,
+    crate::primitive::Color => ColorArray,
+);
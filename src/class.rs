@@ -0,0 +1,94 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::thread::LocalKey;
+
+thread_local! {
+    /// Last handle given to an object of any exported type.
+    static LAST_HANDLE: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Rust type exported to Godot with `#[godot_wasm_bindgen]`.
+///
+/// Every exported type has it's own [`Registry`] and objects are referred by handle.
+/// Handles are unique across all types, so a handle of an object of other type,
+/// or of a freed object, is never accepted.
+/// Handle 0 is never a valid object.
+pub trait GodotClass: Sized + 'static {
+    /// Registry of objects of this type, implemented by the macro.
+    fn registry() -> &'static LocalKey<RefCell<Registry<RefCell<Self>>>>;
+
+    /// Gets object from it's handle.
+    fn from_handle(handle: u32) -> Option<Rc<RefCell<Self>>> {
+        Self::registry().with(|r| r.borrow().get(handle))
+    }
+
+    /// Moves object into registry, returning it's handle.
+    fn into_handle(self) -> u32 {
+        let handle = LAST_HANDLE.with(|last| {
+            let v = match last.get().checked_add(1) {
+                Some(v) => v,
+                None => panic!("Out of handles"),
+            };
+            last.set(v);
+            v
+        });
+        Self::registry().with(|r| {
+            r.borrow_mut()
+                .insert_at(handle, Rc::new(RefCell::new(self)))
+        });
+        handle
+    }
+
+    /// Removes object from registry, invalidating it's handle.
+    fn remove_handle(handle: u32) -> Option<Rc<RefCell<Self>>> {
+        Self::registry().with(|r| r.borrow_mut().remove(handle))
+    }
+}
+
+/// Handle table of exported objects or tasks.
+///
+/// Handles are allocated in increasing order and never reused,
+/// so a stale handle stays invalid instead of referring to a newer object.
+pub struct Registry<T: ?Sized> {
+    objects: BTreeMap<u32, Rc<T>>,
+    last: u32,
+}
+
+impl<T: ?Sized> Default for Registry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> Registry<T> {
+    pub const fn new() -> Self {
+        Self {
+            objects: BTreeMap::new(),
+            last: 0,
+        }
+    }
+
+    pub fn insert(&mut self, v: Rc<T>) -> u32 {
+        self.last = match self.last.checked_add(1) {
+            Some(v) => v,
+            None => panic!("Out of handles"),
+        };
+        self.objects.insert(self.last, v);
+        self.last
+    }
+
+    /// Inserts value under handle allocated elsewhere.
+    pub fn insert_at(&mut self, handle: u32, v: Rc<T>) {
+        self.last = self.last.max(handle);
+        self.objects.insert(handle, v);
+    }
+
+    pub fn get(&self, handle: u32) -> Option<Rc<T>> {
+        self.objects.get(&handle).cloned()
+    }
+
+    pub fn remove(&mut self, handle: u32) -> Option<Rc<T>> {
+        self.objects.remove(&handle)
+    }
+}
@@ -0,0 +1,263 @@
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+
+use crate::godot_array::GodotArray;
+use crate::godot_value::{GodotValue, TypecastErrorOwned, ValueType};
+
+/// Type of converted value, recorded in the bindgen metadata.
+///
+/// It only describes what the host sees, the conversion itself may still fail.
+#[derive(Debug, Clone, Copy)]
+pub enum TypeInfo {
+    /// Value of any type.
+    Any,
+    /// Value of a single type.
+    Value(ValueType),
+    /// Either null or value of the inner type.
+    Optional(&'static TypeInfo),
+    /// Array with elements of the inner type.
+    Array(&'static TypeInfo),
+    /// Array of fixed length, with elements typed by position.
+    Tuple(&'static [TypeInfo]),
+    /// Dictionary with the named fields.
+    Struct(&'static [(&'static str, TypeInfo)]),
+    /// Pool array of the element type.
+    PoolArray(PoolElement),
+}
+
+/// Element of pool array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PoolElement {
+    Byte = 1,
+    Int,
+    Float,
+    String,
+    Vector2,
+    Vector3,
+    Color,
+}
+
+/// Conversion from Godot value, used for exported parameters and imported results.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be converted from Godot value",
+    label = "unsupported type",
+    note = "supported types are primitives, strings, Godot values, arrays and dictionaries, `Option<T>` and tuples of them",
+    note = "use `#[derive(GodotConvert)]` to convert your own type"
+)]
+pub trait FromGodot: Sized {
+    type Error: fmt::Display;
+
+    /// Type of the value converted from.
+    const TYPE: TypeInfo = TypeInfo::Any;
+
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error>;
+}
+
+/// Conversion into Godot value, used for exported results and imported parameters.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be converted into Godot value",
+    label = "unsupported type",
+    note = "supported types are primitives, strings, Godot values, arrays and dictionaries, `Option<T>` and tuples of them",
+    note = "use `#[derive(GodotConvert)]` to convert your own type"
+)]
+pub trait IntoGodot {
+    /// Type of the value converted into.
+    const TYPE: TypeInfo = TypeInfo::Any;
+
+    fn into_godot(self) -> GodotValue;
+}
+
+impl FromGodot for GodotValue {
+    type Error = Infallible;
+
+    #[inline]
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+        Ok(value)
+    }
+}
+
+impl IntoGodot for GodotValue {
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        self
+    }
+}
+
+impl AsRef<GodotValue> for GodotValue {
+    #[inline]
+    fn as_ref(&self) -> &GodotValue {
+        self
+    }
+}
+
+impl IntoGodot for &'_ GodotValue {
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        self.clone()
+    }
+}
+
+/// Null is converted into `None`.
+impl<T: FromGodot> FromGodot for Option<T> {
+    type Error = T::Error;
+
+    const TYPE: TypeInfo = TypeInfo::Optional(&T::TYPE);
+
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_godot(value).map(Some)
+        }
+    }
+}
+
+/// `None` is converted into null.
+impl<T: IntoGodot> IntoGodot for Option<T> {
+    const TYPE: TypeInfo = TypeInfo::Optional(&T::TYPE);
+
+    fn into_godot(self) -> GodotValue {
+        match self {
+            Some(v) => v.into_godot(),
+            None => GodotValue::null(),
+        }
+    }
+}
+
+/// Error of conversion generated by `#[derive(GodotConvert)]`.
+pub enum ConvertError {
+    /// Value is not of the expected type.
+    Typecast(TypecastErrorOwned),
+    /// Dictionary does not have the key of a field.
+    MissingKey(&'static str),
+    /// Integer is not a discriminant of the enum.
+    InvalidDiscriminant(i64),
+    /// String is not a variant name of the enum.
+    UnknownVariant(String),
+    /// Array has different length than the tuple.
+    LengthMismatch { expect: usize, got: usize },
+    /// Value of a field cannot be converted.
+    Field {
+        name: &'static str,
+        error: Box<dyn Error>,
+    },
+    /// Array element cannot be converted.
+    Element { index: usize, error: Box<dyn Error> },
+}
+
+impl ConvertError {
+    pub fn field<E: Error + 'static>(name: &'static str, error: E) -> Self {
+        Self::Field {
+            name,
+            error: Box::new(error),
+        }
+    }
+
+    pub fn element<E: Error + 'static>(index: usize, error: E) -> Self {
+        Self::Element {
+            index,
+            error: Box::new(error),
+        }
+    }
+}
+
+impl From<TypecastErrorOwned> for ConvertError {
+    fn from(v: TypecastErrorOwned) -> Self {
+        Self::Typecast(v)
+    }
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Typecast(e) => write!(f, "{}", e),
+            Self::MissingKey(k) => write!(f, "Missing key {:?}", k),
+            Self::InvalidDiscriminant(v) => write!(f, "Invalid discriminant {}", v),
+            Self::UnknownVariant(v) => write!(f, "Unknown variant {:?}", v),
+            Self::LengthMismatch { expect, got } => {
+                write!(f, "Length mismatch (expected {}, got {})", expect, got)
+            }
+            Self::Field { name, error } => write!(f, "Invalid field {:?}: {}", name, error),
+            Self::Element { index, error } => write!(f, "Invalid element {}: {}", index, error),
+        }
+    }
+}
+
+impl fmt::Debug for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <Self as fmt::Display>::fmt(self, f)
+    }
+}
+
+impl Error for ConvertError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Typecast(e) => Some(e),
+            Self::Field { error, .. } | Self::Element { error, .. } => Some(&**error),
+            _ => None,
+        }
+    }
+}
+
+/// Checks length of array converted positionally.
+#[doc(hidden)]
+pub fn array_check_len(array: &GodotArray, len: usize) -> Result<(), ConvertError> {
+    match array.len() {
+        v if v == len => Ok(()),
+        v => Err(ConvertError::LengthMismatch {
+            expect: len,
+            got: v,
+        }),
+    }
+}
+
+/// Gets and converts array element.
+#[doc(hidden)]
+pub fn array_get<T: FromGodot>(array: &GodotArray, index: usize) -> Result<T, ConvertError>
+where
+    T::Error: Error + 'static,
+{
+    T::from_godot(array.get(index)).map_err(|e| ConvertError::element(index, e))
+}
+
+macro_rules! tuple_convert {
+    ($($n:literal => ($($i:tt : $t:ident),*)),* $(,)?) => {$(
+        /// Tuple is converted positionally from array of the same length.
+        impl<$($t: FromGodot),*> FromGodot for ($($t,)*)
+        where
+            $($t::Error: Error + 'static),*
+        {
+            type Error = ConvertError;
+
+            const TYPE: TypeInfo = TypeInfo::Tuple(&[$($t::TYPE),*]);
+
+            fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+                let a = GodotArray::try_from(value)?;
+                array_check_len(&a, $n)?;
+                Ok(($(array_get::<$t>(&a, $i)?,)*))
+            }
+        }
+
+        /// Tuple is converted positionally into array.
+        impl<$($t: IntoGodot),*> IntoGodot for ($($t,)*) {
+            const TYPE: TypeInfo = TypeInfo::Tuple(&[$($t::TYPE),*]);
+
+            fn into_godot(self) -> GodotValue {
+                GodotArray::from_slice(&[$(self.$i.into_godot()),*]).into()
+            }
+        }
+    )*};
+}
+
+tuple_convert!(
+    1 => (0: A),
+    2 => (0: A, 1: B),
+    3 => (0: A, 1: B, 2: C),
+    4 => (0: A, 1: B, 2: C, 3: D),
+    5 => (0: A, 1: B, 2: C, 3: D, 4: E),
+    6 => (0: A, 1: B, 2: C, 3: D, 4: E, 5: F),
+    7 => (0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G),
+    8 => (0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H),
+);
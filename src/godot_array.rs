@@ -1,6 +1,7 @@
 use std::iter::FusedIterator;
 use std::ops::{Bound, Range, RangeBounds};
 
+use crate::convert::{FromGodot, IntoGodot, TypeInfo};
 use crate::godot_value::{GodotValue, TypecastErrorOwned, ValueType};
 
 #[derive(Debug, Clone)]
@@ -38,6 +39,33 @@ impl From<GodotArray> for GodotValue {
     }
 }
 
+impl AsRef<GodotValue> for GodotArray {
+    #[inline]
+    fn as_ref(&self) -> &GodotValue {
+        &self.value
+    }
+}
+
+impl FromGodot for GodotArray {
+    type Error = TypecastErrorOwned;
+
+    const TYPE: TypeInfo = TypeInfo::Array(&TypeInfo::Any);
+
+    #[inline]
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+        value.try_into()
+    }
+}
+
+impl IntoGodot for GodotArray {
+    const TYPE: TypeInfo = TypeInfo::Array(&TypeInfo::Any);
+
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        self.into()
+    }
+}
+
 #[link(wasm_import_module = "godot_wasm")]
 extern "C" {
     #[link_name = "array.new"]
@@ -121,6 +149,10 @@ impl GodotArray {
         self.len_
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len_ == 0
+    }
+
     pub fn get(&self, ix: usize) -> GodotValue {
         self.check_len(ix);
         unsafe { GodotValue::from_raw(array_get(self.value.to_raw(), ix as _)) }
@@ -274,6 +306,12 @@ pub struct Iter<'a> {
     range: Range<usize>,
 }
 
+impl Default for GodotArray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> IntoIterator for &'a GodotArray {
     type Item = GodotValue;
     type IntoIter = Iter<'a>;
@@ -0,0 +1,163 @@
+use crate::convert::{FromGodot, IntoGodot, TypeInfo};
+use crate::godot_array::GodotArray;
+use crate::godot_value::{GodotValue, TypecastErrorOwned, ValueType};
+
+#[derive(Debug, Clone)]
+pub struct GodotDictionary {
+    value: GodotValue,
+}
+
+impl TryFrom<GodotValue> for GodotDictionary {
+    type Error = TypecastErrorOwned;
+
+    fn try_from(value: GodotValue) -> Result<Self, Self::Error> {
+        match (&value).into() {
+            ValueType::Dictionary => Ok(Self { value }),
+            v => Err(Self::Error::new(value, ValueType::Dictionary, v)),
+        }
+    }
+}
+
+impl From<GodotValue> for Option<GodotDictionary> {
+    fn from(value: GodotValue) -> Self {
+        value.try_into().ok()
+    }
+}
+
+impl From<GodotDictionary> for GodotValue {
+    fn from(value: GodotDictionary) -> Self {
+        value.value
+    }
+}
+
+impl AsRef<GodotValue> for GodotDictionary {
+    #[inline]
+    fn as_ref(&self) -> &GodotValue {
+        &self.value
+    }
+}
+
+impl FromGodot for GodotDictionary {
+    type Error = TypecastErrorOwned;
+
+    const TYPE: TypeInfo = TypeInfo::Value(ValueType::Dictionary);
+
+    #[inline]
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+        value.try_into()
+    }
+}
+
+impl IntoGodot for GodotDictionary {
+    const TYPE: TypeInfo = TypeInfo::Value(ValueType::Dictionary);
+
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        self.into()
+    }
+}
+
+#[link(wasm_import_module = "godot_wasm")]
+extern "C" {
+    #[link_name = "dictionary.new"]
+    fn dictionary_new() -> u32;
+    #[link_name = "dictionary.len"]
+    fn dictionary_len(ptr: u32) -> u32;
+    #[link_name = "dictionary.has"]
+    fn dictionary_has(ptr: u32, k: u32) -> u32;
+    #[link_name = "dictionary.get"]
+    fn dictionary_get(ptr: u32, k: u32) -> u32;
+    #[link_name = "dictionary.set"]
+    fn dictionary_set(ptr: u32, k: u32, v: u32);
+    #[link_name = "dictionary.erase"]
+    fn dictionary_erase(ptr: u32, k: u32) -> u32;
+    #[link_name = "dictionary.clear"]
+    fn dictionary_clear(ptr: u32);
+    #[link_name = "dictionary.keys"]
+    fn dictionary_keys(ptr: u32) -> u32;
+    #[link_name = "dictionary.values"]
+    fn dictionary_values(ptr: u32) -> u32;
+    #[link_name = "dictionary.duplicate"]
+    fn dictionary_duplicate(ptr: u32) -> u32;
+}
+
+impl GodotDictionary {
+    pub fn new() -> Self {
+        Self {
+            value: unsafe { GodotValue::from_raw(dictionary_new()) },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { dictionary_len(self.value.to_raw()) as _ }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains_key(&self, k: &GodotValue) -> bool {
+        unsafe { dictionary_has(self.value.to_raw(), k.to_raw()) != 0 }
+    }
+
+    /// Gets value of key, or `None` if the key does not exist.
+    pub fn get(&self, k: &GodotValue) -> Option<GodotValue> {
+        if self.contains_key(k) {
+            unsafe {
+                Some(GodotValue::from_raw(dictionary_get(
+                    self.value.to_raw(),
+                    k.to_raw(),
+                )))
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn insert(&mut self, k: &GodotValue, v: &GodotValue) {
+        unsafe { dictionary_set(self.value.to_raw(), k.to_raw(), v.to_raw()) }
+    }
+
+    /// Removes key, returning if it exists.
+    pub fn remove(&mut self, k: &GodotValue) -> bool {
+        unsafe { dictionary_erase(self.value.to_raw(), k.to_raw()) != 0 }
+    }
+
+    pub fn clear(&mut self) {
+        unsafe { dictionary_clear(self.value.to_raw()) }
+    }
+
+    /// Gets array of keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if host does not return an array, which only happens on a broken host.
+    pub fn keys(&self) -> GodotArray {
+        let v = unsafe { GodotValue::from_raw(dictionary_keys(self.value.to_raw())) };
+        v.try_into().expect("Keys is not an array")
+    }
+
+    /// Gets array of values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if host does not return an array, which only happens on a broken host.
+    pub fn values(&self) -> GodotArray {
+        let v = unsafe { GodotValue::from_raw(dictionary_values(self.value.to_raw())) };
+        v.try_into().expect("Values is not an array")
+    }
+
+    pub fn duplicate(&self) -> GodotDictionary {
+        unsafe {
+            Self {
+                value: GodotValue::from_raw(dictionary_duplicate(self.value.to_raw())),
+            }
+        }
+    }
+}
+
+impl Default for GodotDictionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,6 +2,7 @@ use std::iter::FusedIterator;
 use std::ops::{Bound, Range};
 use std::{fmt, ops::RangeBounds};
 
+use crate::convert::{FromGodot, IntoGodot, PoolElement, TypeInfo};
 use crate::godot_value::{GodotValue, TypecastErrorOwned, ValueType};
 
 #[derive(Debug, Clone)]
@@ -31,6 +32,10 @@ impl GodotString {
         self.len_
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len_ == 0
+    }
+
     pub fn extend_string(&self, s: &mut String) {
         let len = self.len();
 
@@ -119,12 +124,67 @@ where
     }
 }
 
+impl AsRef<GodotValue> for GodotString {
+    #[inline]
+    fn as_ref(&self) -> &GodotValue {
+        &self.value
+    }
+}
+
 impl From<GodotString> for GodotValue {
     fn from(v: GodotString) -> Self {
         v.value
     }
 }
 
+impl FromGodot for GodotString {
+    type Error = TypecastErrorOwned;
+
+    const TYPE: TypeInfo = TypeInfo::Value(ValueType::GodotString);
+
+    #[inline]
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+        value.try_into()
+    }
+}
+
+impl IntoGodot for GodotString {
+    const TYPE: TypeInfo = TypeInfo::Value(ValueType::GodotString);
+
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        self.into()
+    }
+}
+
+impl FromGodot for String {
+    type Error = TypecastErrorOwned;
+
+    const TYPE: TypeInfo = TypeInfo::Value(ValueType::GodotString);
+
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+        GodotString::try_from(value).map(|v| (&v).into())
+    }
+}
+
+impl IntoGodot for String {
+    const TYPE: TypeInfo = TypeInfo::Value(ValueType::GodotString);
+
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        GodotString::from(self).into()
+    }
+}
+
+impl IntoGodot for &'_ str {
+    const TYPE: TypeInfo = TypeInfo::Value(ValueType::GodotString);
+
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        GodotString::from(self).into()
+    }
+}
+
 impl From<&'_ GodotString> for String {
     fn from(v: &GodotString) -> Self {
         let ptr = unsafe { v.value.to_raw() };
@@ -177,6 +237,10 @@ impl StringArray {
         self.len_
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len_ == 0
+    }
+
     pub fn get(&self, ix: usize) -> Option<GodotString> {
         if ix >= self.len() {
             None
@@ -269,6 +333,33 @@ impl From<StringArray> for GodotValue {
     }
 }
 
+impl AsRef<GodotValue> for StringArray {
+    #[inline]
+    fn as_ref(&self) -> &GodotValue {
+        &self.value
+    }
+}
+
+impl FromGodot for StringArray {
+    type Error = TypecastErrorOwned;
+
+    const TYPE: TypeInfo = TypeInfo::PoolArray(PoolElement::String);
+
+    #[inline]
+    fn from_godot(value: GodotValue) -> Result<Self, Self::Error> {
+        value.try_into()
+    }
+}
+
+impl IntoGodot for StringArray {
+    const TYPE: TypeInfo = TypeInfo::PoolArray(PoolElement::String);
+
+    #[inline]
+    fn into_godot(self) -> GodotValue {
+        self.into()
+    }
+}
+
 impl<'a> FromIterator<&'a GodotString> for StringArray {
     fn from_iter<T: IntoIterator<Item = &'a GodotString>>(it: T) -> Self {
         // SAFETY: We will drop all value later.
@@ -277,16 +368,14 @@ impl<'a> FromIterator<&'a GodotString> for StringArray {
             .map(|v| unsafe { v.value.to_raw() })
             .collect();
 
-        let ret = unsafe {
+        unsafe {
             let r = v.as_ptr_range();
             // SAFETY: It is always a string array
             Self {
                 value: GodotValue::from_raw(build_string_array(r.start, r.end)),
                 len_: v.len(),
             }
-        };
-
-        ret
+        }
     }
 }
 
@@ -1,7 +1,10 @@
+use std::error::Error;
 use std::fmt;
 use std::marker::PhantomData;
 use std::mem;
 
+use crate::convert::{FromGodot, IntoGodot, PoolElement, TypeInfo};
+
 #[derive(Debug)]
 #[repr(transparent)]
 pub struct GodotValue {
@@ -106,11 +109,21 @@ macro_rules! typeis {
 }
 
 impl GodotValue {
+    /// Gets the raw handle of the value.
+    ///
+    /// # Safety
+    ///
+    /// The handle is still owned by `self` and must not be freed.
     #[inline]
     pub unsafe fn to_raw(&self) -> u32 {
         self.ptr
     }
 
+    /// Converts the value into its raw handle.
+    ///
+    /// # Safety
+    ///
+    /// The caller takes ownership of the handle and is responsible for freeing it.
     #[inline]
     pub unsafe fn into_raw(self) -> u32 {
         let ret = self.ptr;
@@ -118,6 +131,11 @@ impl GodotValue {
         ret
     }
 
+    /// Creates value from a raw handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be valid (or null) and owned by no other value.
     #[inline]
     pub unsafe fn from_raw(ptr: u32) -> Self {
         Self {
@@ -126,6 +144,15 @@ impl GodotValue {
         }
     }
 
+    /// Creates null value.
+    #[inline]
+    pub const fn null() -> Self {
+        Self {
+            ptr: 0,
+            phantom: PhantomData,
+        }
+    }
+
     #[inline]
     pub const fn is_null(&self) -> bool {
         self.ptr == 0
@@ -207,6 +234,8 @@ impl fmt::Debug for TypecastError {
     }
 }
 
+impl Error for TypecastError {}
+
 pub struct TypecastErrorOwned {
     pub original: GodotValue,
 
@@ -253,6 +282,8 @@ impl fmt::Debug for TypecastErrorOwned {
     }
 }
 
+impl Error for TypecastErrorOwned {}
+
 macro_rules! typecast {
     (@typefrom $t:ty) => {$t};
     (@typeto $t:ty) => {$t};
@@ -314,6 +345,26 @@ macro_rules! typecast {
                     unsafe { Self::from_raw($wfunc(&v.into() as _)) }
                 }
             }
+
+            impl FromGodot for typecast!(@typefrom $($t)*) {
+                type Error = TypecastErrorOwned;
+
+                const TYPE: TypeInfo = TypeInfo::Value(ValueType::$vname);
+
+                #[inline]
+                fn from_godot(v: GodotValue) -> Result<Self, Self::Error> {
+                    v.try_into()
+                }
+            }
+
+            impl IntoGodot for typecast!(@typefrom $($t)*) {
+                const TYPE: TypeInfo = TypeInfo::Value(ValueType::$vname);
+
+                #[inline]
+                fn into_godot(self) -> GodotValue {
+                    self.into()
+                }
+            }
         )*
     };
 }
@@ -355,6 +406,26 @@ macro_rules! typecast_proxy {
                 Self::from(v as $from)
             }
         }
+
+        impl FromGodot for $to {
+            type Error = TypecastErrorOwned;
+
+            const TYPE: TypeInfo = <$from as FromGodot>::TYPE;
+
+            #[inline]
+            fn from_godot(v: GodotValue) -> Result<Self, Self::Error> {
+                v.try_into()
+            }
+        }
+
+        impl IntoGodot for $to {
+            const TYPE: TypeInfo = <$from as IntoGodot>::TYPE;
+
+            #[inline]
+            fn into_godot(self) -> GodotValue {
+                self.into()
+            }
+        }
     )*};
 }
 
@@ -368,9 +439,9 @@ impl From<bool> for BoolWrapper {
     }
 }
 
-impl Into<bool> for BoolWrapper {
-    fn into(self) -> bool {
-        self.0 != 0
+impl From<BoolWrapper> for bool {
+    fn from(value: BoolWrapper) -> Self {
+        value.0 != 0
     }
 }
 
@@ -401,7 +472,7 @@ typecast_proxy!(
 );
 
 macro_rules! typecast_pool {
-    ($($t:ty : $l:literal => [
+    ($($t:ty : $l:literal => $elem:ident [
         $vname:ident => $ifunc:ident,
         $lfunc:ident => $lname:literal,
         $rfunc:ident => $rname:literal,
@@ -473,42 +544,71 @@ macro_rules! typecast_pool {
                     unsafe { Self::from_raw($wfunc(v.as_ptr(), v.len() as _)) }
                 }
             }
+
+            impl FromGodot for Vec<$t> {
+                type Error = TypecastErrorOwned;
+
+                const TYPE: TypeInfo = TypeInfo::PoolArray(PoolElement::$elem);
+
+                #[inline]
+                fn from_godot(v: GodotValue) -> Result<Self, Self::Error> {
+                    v.try_into()
+                }
+            }
+
+            impl IntoGodot for Vec<$t> {
+                const TYPE: TypeInfo = TypeInfo::PoolArray(PoolElement::$elem);
+
+                #[inline]
+                fn into_godot(self) -> GodotValue {
+                    self.into()
+                }
+            }
+
+            impl IntoGodot for &[$t] {
+                const TYPE: TypeInfo = TypeInfo::PoolArray(PoolElement::$elem);
+
+                #[inline]
+                fn into_godot(self) -> GodotValue {
+                    self.into()
+                }
+            }
         )*
     };
 }
 
 typecast_pool!(
-    u8: 1 => [
+    u8: 1 => Byte [
         ByteArray => is_byte_array,
         len_byte_array => "byte_array.len",
         read_byte_array => "byte_array.read",
         write_byte_array => "byte_array.write"
     ],
-    u32: 4 => [
+    u32: 4 => Int [
         IntArray => is_int_array,
         len_int_array => "int_array.len",
         read_int_array => "int_array.read",
         write_int_array => "int_array.write"
     ],
-    f32: 4 => [
+    f32: 4 => Float [
         FloatArray => is_float_array,
         len_float_array => "float_array.len",
         read_float_array => "float_array.read",
         write_float_array => "float_array.write"
     ],
-    crate::primitive::Vector2: 8 => [
+    crate::primitive::Vector2: 8 => Vector2 [
         Vector2Array => is_vector2_array,
         len_vector2_array => "vector2_array.len",
         read_vector2_array => "vector2_array.read",
         write_vector2_array => "vector2_array.write"
     ],
-    crate::primitive::Vector3: 12 => [
+    crate::primitive::Vector3: 12 => Vector3 [
         Vector3Array => is_vector3_array,
         len_vector3_array => "vector3_array.len",
         read_vector3_array => "vector3_array.read",
         write_vector3_array => "vector3_array.write"
     ],
-    crate::primitive::Color: 16 => [
+    crate::primitive::Color: 16 => Color [
         ColorArray => is_color_array,
         len_color_array => "color_array.len",
         read_color_array => "color_array.read",
@@ -0,0 +1,31 @@
+use std::cell::OnceCell;
+use std::thread::LocalKey;
+
+/// Value provided by host, imported with `static` in a `#[godot_wasm_bindgen]` extern block.
+///
+/// The declared type `T` of the static is wrapped into `HostStatic<T>` by the macro.
+/// The value is fetched on first access and cached afterwards.
+pub struct HostStatic<T: 'static> {
+    cache: &'static LocalKey<OnceCell<T>>,
+    fetch: fn() -> T,
+}
+
+impl<T: 'static> HostStatic<T> {
+    #[doc(hidden)]
+    pub const fn new(cache: &'static LocalKey<OnceCell<T>>, fetch: fn() -> T) -> Self {
+        Self { cache, fetch }
+    }
+
+    /// Calls `f` with reference to the value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.cache.with(|v| f(v.get_or_init(self.fetch)))
+    }
+
+    /// Gets a copy of the value.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+}
@@ -1,10 +1,17 @@
 #[macro_use]
 extern crate static_assertions;
+// Allows the crate itself to use #[godot_wasm_bindgen].
+extern crate self as godot_wasm_bindgen;
 
 #[doc(hidden)]
 pub mod __hidden;
+pub mod class;
+pub mod convert;
 pub mod godot_array;
+pub mod godot_dictionary;
 pub mod godot_string;
 pub mod godot_value;
+pub mod host_static;
 pub mod prelude;
 pub mod primitive;
+pub mod task;
@@ -1,6 +1,10 @@
-pub use godot_wasm_bindgen_macro::godot_wasm_bindgen;
+pub use godot_wasm_bindgen_macro::{godot_wasm_bindgen, GodotConvert};
 
+pub use crate::class::GodotClass;
+pub use crate::convert::{ConvertError, FromGodot, IntoGodot, TypeInfo};
 pub use crate::godot_array::GodotArray;
+pub use crate::godot_dictionary::GodotDictionary;
 pub use crate::godot_string::{GodotString, StringArray};
 pub use crate::godot_value::{GodotValue, ValueType};
+pub use crate::host_static::HostStatic;
 pub use crate::primitive::*;
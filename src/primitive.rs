@@ -304,6 +304,7 @@ macro_rules! impl_binop {
             $arg : impl_binop!(@type $a $b $f $arg $($e)?),
         )*}
     };
+    (@ret $a:ident $b:ident $f:ident { $e:expr }) => { $e };
     (@ret $a:ident $b:ident $f:ident $e:expr) => { $e };
     (<$f:ident ($a:ident, $b:ident) : $i:ident> : []) => {};
     (
@@ -718,7 +719,7 @@ impl_binop!(
             size: a.size / b,
         },
         [Quat] {
-            a * b.conjugate()
+            Mul::mul(a, b.conjugate())
         },
     ]
 );
@@ -0,0 +1,115 @@
+//! Executor of async exported functions.
+//!
+//! Calling an async exported function spawns a task and returns it's handle.
+//! The host then drives the task:
+//! - `__godot_wasm_bindgen_poll(task)` polls the task, returning `[ready, result]`.
+//!   Once ready, the task is finished and it's handle is invalidated.
+//! - `__godot_wasm_bindgen_cancel(task)` drops the task.
+//! - The host must provide `host.task.wake(task)`, which is called when a pending task
+//!   should be polled again. It may be called while the task is being polled,
+//!   so the host must defer the poll instead of polling synchronously.
+//!
+//! The CLI removes the executor from modules without async functions,
+//! so only modules with async functions import `host.task.wake`.
+//!
+//! Task handles are never reused. Polling or cancelling a finished task does nothing,
+//! as a wake may still arrive after the task is finished.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use crate::class::Registry;
+use crate::godot_value::GodotValue;
+use crate::prelude::godot_wasm_bindgen;
+
+type BoxedFuture = Pin<Box<dyn Future<Output = GodotValue>>>;
+
+thread_local! {
+    static TASKS: RefCell<Registry<RefCell<BoxedFuture>>> = const { RefCell::new(Registry::new()) };
+}
+
+#[godot_wasm_bindgen(use_native_types)]
+extern "C" {
+    /// Notifies host that the task should be polled again.
+    #[link_name = "task.wake"]
+    fn wake(task: u32);
+}
+
+struct TaskWaker(u32);
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // Waker may outlive it's task.
+        if TASKS.with(|r| r.borrow().get(self.0).is_some()) {
+            unsafe { wake(self.0) }
+        }
+    }
+}
+
+/// Spawns a task, returning it's handle.
+pub fn spawn(future: impl Future<Output = GodotValue> + 'static) -> u32 {
+    TASKS.with(|r| {
+        r.borrow_mut()
+            .insert(Rc::new(RefCell::new(Box::pin(future))))
+    })
+}
+
+/// Yields to the host, resuming on the next poll.
+pub fn yield_now() -> impl Future<Output = ()> {
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    YieldNow(false)
+}
+
+/// Polls a task, returning if it's ready and it's result.
+///
+/// Finished task is never ready again.
+#[godot_wasm_bindgen(name = "__godot_wasm_bindgen_poll", returns = ("ready", "result"))]
+pub fn poll(#[native] task: u32) -> (bool, GodotValue) {
+    // Registry must not be borrowed while polling, as the task might spawn another task.
+    let future = match TASKS.with(|r| r.borrow().get(task)) {
+        Some(v) => v,
+        None => return (false, GodotValue::null()),
+    };
+
+    let waker = Waker::from(Arc::new(TaskWaker(task)));
+    let ret = match future.try_borrow_mut() {
+        Ok(mut v) => v.as_mut().poll(&mut Context::from_waker(&waker)),
+        Err(_) => panic!("Task {} polled while being polled (from task.wake?)", task),
+    };
+    match ret {
+        Poll::Ready(v) => {
+            TASKS.with(|r| r.borrow_mut().remove(task));
+            (true, v)
+        }
+        Poll::Pending => (false, GodotValue::null()),
+    }
+}
+
+/// Drops an unfinished task, doing nothing if it's already finished.
+#[godot_wasm_bindgen(name = "__godot_wasm_bindgen_cancel")]
+pub fn cancel(#[native] task: u32) {
+    TASKS.with(|r| r.borrow_mut().remove(task));
+}